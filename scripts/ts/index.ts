@@ -95,11 +95,17 @@ const SUBSCRIPTION_HUB_CODE_ID = process.env.SUBSCRIPTION_HUB_CODE_ID || "";
           organization_id: organizationId,
           name: "My New Subscription Plan",
           description: "This is my new subscription plan",
-          price: "500000", // 0.5
+          prices: [
+            {
+              info: { native: { denom: "aconst" } },
+              amount: "500000",
+            },
+          ],
           duration: 1,
           duration_unit: "month",
           cancelable: true,
           refundable: false,
+          transferable: false,
         },
       },
       "auto"
@@ -136,7 +142,9 @@ const SUBSCRIPTION_HUB_CODE_ID = process.env.SUBSCRIPTION_HUB_CODE_ID || "";
           plan_id: planId,
         },
       },
-      "auto"
+      "auto",
+      undefined,
+      [{ denom: "aconst", amount: "500000" }]
     );
 
     console.log("🟠 Subscribe Plan TxHash: ", res.transactionHash, "\n");
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, MessageInfo, StdResult, Uint128, WasmMsg,
+};
+
+use crate::error::ContractError;
+
+#[cw_serde]
+pub enum AssetInfo {
+    // Native token with the given denom
+    Native { denom: String },
+    // CW20 token with the given contract address
+    Cw20 { address: Addr },
+}
+
+impl AssetInfo {
+    // Validates the CW20 contract address of the asset
+    pub fn validate(&self, api: &dyn Api) -> StdResult<()> {
+        if let AssetInfo::Cw20 { address } = self {
+            api.addr_validate(address.as_str())?;
+        }
+        Ok(())
+    }
+
+    // Storage key of the asset
+    pub fn key(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for AssetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetInfo::Native { denom } => write!(f, "native:{}", denom),
+            AssetInfo::Cw20 { address } => write!(f, "cw20:{}", address),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Asset {
+    // The asset type
+    pub info: AssetInfo,
+    // The amount of the asset
+    pub amount: Uint128,
+}
+
+impl Asset {
+    // Creates a message that sends the asset to the recipient
+    pub fn transfer_msg(&self, recipient: &Addr) -> StdResult<CosmosMsg> {
+        Ok(match &self.info {
+            AssetInfo::Native { denom } => BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount: self.amount,
+                }],
+            }
+            .into(),
+            AssetInfo::Cw20 { address } => WasmMsg::Execute {
+                contract_addr: address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: self.amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        })
+    }
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.info, self.amount)
+    }
+}
+
+// Extracts the native token payment sent with the message
+pub fn native_payment(info: &MessageInfo) -> Result<Option<Asset>, ContractError> {
+    match info.funds.as_slice() {
+        [] => Ok(None),
+        [coin] => Ok(Some(Asset {
+            info: AssetInfo::Native {
+                denom: coin.denom.clone(),
+            },
+            amount: coin.amount,
+        })),
+        _ => Err(ContractError::InvalidPayment {}),
+    }
+}
+
+// The subset of the CW20 execute messages used by the hub
+#[cw_serde]
+enum Cw20ExecuteMsg {
+    Transfer { recipient: String, amount: Uint128 },
+}
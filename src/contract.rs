@@ -3,38 +3,79 @@ use std::collections::BTreeMap;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Uint128,
+    from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, Event, MessageInfo, Order, Reply,
+    Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, Timestamp, Uint128,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
 use cw_utils::maybe_addr;
+use sha2::{Digest, Sha256};
 
+use crate::asset::{native_payment, Asset, AssetInfo};
+use crate::cw721;
 use crate::error::ContractError;
+use crate::events;
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, OrganizationResponse, QueryMsg, SubscriptionPlanResponse,
-    SubscriptionResponse,
+    CouponResponse, CreditEntryResponse, Cw20BalanceResponse, Cw20QueryMsg, Cw20ReceiveMsg,
+    Cw721QueryMsg, ExecuteMsg, InstallmentsResponse, InstantiateMsg, OraclePriceResponse,
+    OracleQueryMsg, OrganizationResponse, PlanCapacityResponse, QueryMsg, ReceiveMsg,
+    RecipientRevenueResponse, ReferrerStatsResponse, SubscriptionHookMsg, SubscriptionPlanResponse,
+    SubscriptionResponse, TokensResponse, UsagePeriod, UsageResponse, WaitlistEntry,
 };
 use crate::state::{
-    Config, DurationUnit, Organization, Subscription, SubscriptionPlan, CONFIG, ORGANIZATIONS,
-    ORGANIZATION_ID, ORGANIZATION_SUBSCRIPTION_PLANS, SUBSCRIPTIONS, SUBSCRIPTION_ID,
-    SUBSCRIPTION_PLANS, SUBSCRIPTION_PLAN_ID, SUBSCRIPTION_PLAN_SUBSCRIPTIONS, USER_ORGANIZATIONS,
-    USER_SUBSCRIPTIONS,
+    AffiliateReward, AppliedCoupon, Bundle, BundleComponent, BundleSubscription, Config, Coupon,
+    CouponDuration, CreditEntry, CreditEntryKind, Discount, DunningPolicy, DurationUnit, Escrow,
+    Hook, HookFailurePolicy, InstallmentSchedule, Organization, PeriodUsage, PlanAccess, PlanKind,
+    ReferencePrice, RevenueShare, Subscription, SubscriptionPlan, SubscriptionStatus, TokenGate,
+    TokenRequirement, ValidationLimits, Verification, ACTIVE_SUBSCRIBERS, AFFILIATE_REWARDS,
+    BUNDLED_SUBSCRIPTIONS, BUNDLES, BUNDLE_ID, BUNDLE_SUBSCRIPTIONS, BUNDLE_SUBSCRIPTION_ID,
+    CONFIG, COUPONS, COUPON_REDEMPTIONS, CREDITS, CREDIT_LEDGER, CREDIT_LEDGER_ID, ESCROWS, HOOKS,
+    NEVER_EXPIRES, ORGANIZATIONS, ORGANIZATION_HANDLES, ORGANIZATION_ID, ORGANIZATION_REVENUE,
+    ORGANIZATION_SUBSCRIPTION_PLANS, OWNER_TOKENS, PLAN_ACCESS, PLAN_ALLOWLIST, RECIPIENT_REVENUE,
+    REFERRALS, REFERRAL_EARNINGS, REFERRAL_REWARDS, RELEASED_HANDLES, REVENUE_SPLITS, SEATS,
+    SUBSCRIPTIONS, SUBSCRIPTION_ID, SUBSCRIPTION_PLANS, SUBSCRIPTION_PLAN_ID,
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS, TOKEN_APPROVALS, TOKEN_GATES, USAGE, USAGE_REPORTERS,
+    USER_ORGANIZATIONS, USER_SUBSCRIPTIONS, VERIFICATIONS, VERIFIERS, WAITLIST,
+    WAITLIST_ADMISSIONS, WAITLIST_NEXT_POSITION, WAITLIST_POSITIONS,
 };
+use crate::validation;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:subscription-hub";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Oracle prices older than an hour are rejected by default
+const DEFAULT_ORACLE_MAX_STALENESS: u64 = 3600;
+
+// Seconds an organization has to wait between handle changes
+const HANDLE_CHANGE_COOLDOWN: u64 = 30 * 24 * 60 * 60;
+
+// Seconds a released handle stays reserved for its previous organization
+const HANDLE_RESERVATION_PERIOD: u64 = 90 * 24 * 60 * 60;
+
+// Maximum number of recipients of a revenue split
+const MAX_REVENUE_RECIPIENTS: usize = 10;
+
+// Reply id of the hook messages whose failure is ignored
+const HOOK_REPLY_ID: u64 = 1;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let config = Config { admin: info.sender };
+    let config = Config {
+        admin: info.sender,
+        oracle: maybe_addr(deps.api, msg.oracle)?,
+        oracle_max_staleness: msg
+            .oracle_max_staleness
+            .unwrap_or(DEFAULT_ORACLE_MAX_STALENESS),
+        limits: msg.limits.unwrap_or_default(),
+    };
     CONFIG.save(deps.storage, &config)?;
 
     // Initialize the ID counters
@@ -55,23 +96,59 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
+        ExecuteMsg::UpdateConfig {
+            admin,
+            oracle,
+            oracle_max_staleness,
+            limits,
+        } => execute_update_config(deps, env, info, admin, oracle, oracle_max_staleness, limits),
         ExecuteMsg::CreateOrganization {
             name,
             description,
+            handle,
+            website,
+            metadata,
+        } => execute_create_organization(
+            deps,
+            env,
+            info,
+            name,
+            description,
+            handle,
             website,
             metadata,
-        } => execute_create_organization(deps, env, info, name, description, website, metadata),
+        ),
+        ExecuteMsg::SetVerifiers { verifiers } => execute_set_verifiers(deps, env, info, verifiers),
+        ExecuteMsg::VerifyOrganization {
+            organization_id,
+            proof_url,
+        } => execute_verify_organization(deps, env, info, organization_id, proof_url),
+        ExecuteMsg::RevokeVerification { organization_id } => {
+            execute_revoke_verification(deps, env, info, organization_id)
+        }
+        ExecuteMsg::SetOrganizationHandle {
+            organization_id,
+            handle,
+        } => execute_set_organization_handle(deps, env, info, organization_id, handle),
         ExecuteMsg::CreateSubscriptionPlan {
             organization_id,
             name,
             description,
-            price,
+            prices,
+            reference_price,
+            kind,
+            dunning,
             duration,
             duration_unit,
             features,
             metadata,
             cancelable,
             refundable,
+            transferable,
+            pausable,
+            max_pause_duration,
+            max_subscribers,
+            waitlist,
         } => execute_create_subscription_plan(
             deps,
             env,
@@ -79,40 +156,312 @@ pub fn execute(
             organization_id,
             name,
             description,
-            price,
+            prices,
+            reference_price,
+            kind,
+            dunning,
             duration,
             duration_unit,
             features,
             metadata,
             cancelable,
             refundable,
+            transferable,
+            pausable,
+            max_pause_duration,
+            max_subscribers,
+            waitlist,
+        ),
+        ExecuteMsg::SubscribePlan {
+            plan_id,
+            beneficiary,
+            coupon,
+            max_price,
+            proof,
+            referrer,
+        } => {
+            let payment = native_payment(&info)?;
+            execute_subscribe_plan(
+                deps,
+                env,
+                info.sender,
+                payment,
+                plan_id,
+                beneficiary,
+                coupon,
+                max_price,
+                proof,
+                referrer,
+            )
+        }
+        ExecuteMsg::RenewSubscription {
+            subscription_id,
+            max_price,
+        } => {
+            let payment = native_payment(&info)?;
+            execute_renew_subscription(deps, env, info.sender, payment, subscription_id, max_price)
+        }
+        ExecuteMsg::ChangePlan {
+            subscription_id,
+            plan_id,
+            max_price,
+            proof,
+        } => {
+            let payment = native_payment(&info)?;
+            execute_change_plan(
+                deps,
+                env,
+                info.sender,
+                payment,
+                subscription_id,
+                plan_id,
+                max_price,
+                proof,
+            )
+        }
+        ExecuteMsg::TopUp { subscription_id } => {
+            let payment = native_payment(&info)?;
+            execute_top_up(deps, env, info.sender, payment, subscription_id)
+        }
+        ExecuteMsg::PayInstallment { subscription_id } => {
+            let payment = native_payment(&info)?;
+            execute_pay_installment(deps, env, info.sender, payment, subscription_id)
+        }
+        ExecuteMsg::ReportUsage {
+            subscription_id,
+            units,
+        } => execute_report_usage(deps, env, info, subscription_id, units),
+        ExecuteMsg::PauseSubscription { subscription_id } => {
+            execute_pause_subscription(deps, env, info, subscription_id)
+        }
+        ExecuteMsg::ResumeSubscription { subscription_id } => {
+            execute_resume_subscription(deps, env, info, subscription_id)
+        }
+        ExecuteMsg::ProcessSubscription { subscription_id } => {
+            execute_process_subscription(deps, env, subscription_id)
+        }
+        ExecuteMsg::SetUsageReporters {
+            organization_id,
+            reporters,
+        } => execute_set_usage_reporters(deps, env, info, organization_id, reporters),
+        ExecuteMsg::AddHook {
+            organization_id,
+            contract,
+            on_failure,
+        } => execute_add_hook(deps, env, info, organization_id, contract, on_failure),
+        ExecuteMsg::RemoveHook {
+            organization_id,
+            contract,
+        } => execute_remove_hook(deps, env, info, organization_id, contract),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::DepositCredit { recipient } => {
+            let payment = native_payment(&info)?;
+            execute_deposit_credit(deps, env, info.sender, payment, recipient)
+        }
+        ExecuteMsg::WithdrawCredit { asset, amount } => {
+            execute_withdraw_credit(deps, env, info, asset, amount)
+        }
+        ExecuteMsg::WithdrawRevenue {
+            organization_id,
+            asset,
+            amount,
+        } => execute_withdraw_revenue(deps, env, info, organization_id, asset, amount),
+        ExecuteMsg::SetRevenueSplit {
+            organization_id,
+            shares,
+        } => execute_set_revenue_split(deps, env, info, organization_id, shares),
+        ExecuteMsg::ClaimRevenue {
+            organization_id,
+            asset,
+        } => execute_claim_revenue(deps, env, info, organization_id, asset),
+        ExecuteMsg::JoinWaitlist { plan_id } => execute_join_waitlist(deps, env, info, plan_id),
+        ExecuteMsg::LeaveWaitlist { plan_id } => execute_leave_waitlist(deps, env, info, plan_id),
+        ExecuteMsg::AdmitFromWaitlist { plan_id, count } => {
+            execute_admit_from_waitlist(deps, env, info, plan_id, count)
+        }
+        ExecuteMsg::SetPlanAccess { plan_id, access } => {
+            execute_set_plan_access(deps, env, info, plan_id, access)
+        }
+        ExecuteMsg::UpdatePlanAllowlist {
+            plan_id,
+            add,
+            remove,
+        } => execute_update_plan_allowlist(deps, env, info, plan_id, add, remove),
+        ExecuteMsg::SetAffiliateReward { plan_id, reward } => {
+            execute_set_affiliate_reward(deps, env, info, plan_id, reward)
+        }
+        ExecuteMsg::ClaimReferralRewards {} => execute_claim_referral_rewards(deps, env, info),
+        ExecuteMsg::SetTokenGate { plan_id, gate } => {
+            execute_set_token_gate(deps, env, info, plan_id, gate)
+        }
+        ExecuteMsg::CreateBundle {
+            organization_id,
+            name,
+            components,
+            prices,
+            duration,
+            duration_unit,
+        } => execute_create_bundle(
+            deps,
+            env,
+            info,
+            organization_id,
+            name,
+            components,
+            prices,
+            duration,
+            duration_unit,
         ),
-        ExecuteMsg::SubscribePlan { plan_id } => execute_subscribe_plan(deps, env, info, plan_id),
+        ExecuteMsg::ApproveBundle {
+            bundle_id,
+            organization_id,
+        } => execute_approve_bundle(deps, env, info, bundle_id, organization_id),
+        ExecuteMsg::SubscribeBundle {
+            bundle_id,
+            beneficiary,
+        } => {
+            let payment = native_payment(&info)?;
+            execute_subscribe_bundle(deps, env, info.sender, payment, bundle_id, beneficiary)
+        }
+        ExecuteMsg::RenewBundle {
+            bundle_subscription_id,
+        } => {
+            let payment = native_payment(&info)?;
+            execute_renew_bundle(deps, env, info.sender, payment, bundle_subscription_id)
+        }
+        ExecuteMsg::CancelBundle {
+            bundle_subscription_id,
+        } => execute_cancel_bundle(deps, env, info, bundle_subscription_id),
         ExecuteMsg::CancelPlan { plan_id } => execute_cancel_plan(deps, env, info, plan_id),
+        ExecuteMsg::TransferSubscription {
+            subscription_id,
+            recipient,
+        } => execute_transfer_subscription(deps, env, info, subscription_id, recipient),
+        ExecuteMsg::CreateCoupon {
+            organization_id,
+            code,
+            discount,
+            duration,
+            max_redemptions,
+            per_user_limit,
+            expiration,
+            plan_ids,
+        } => execute_create_coupon(
+            deps,
+            env,
+            info,
+            organization_id,
+            code,
+            discount,
+            duration,
+            max_redemptions,
+            per_user_limit,
+            expiration,
+            plan_ids,
+        ),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => cw721::execute_transfer_nft(deps, env, info, recipient, token_id),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => cw721::execute_send_nft(deps, env, info, contract, token_id, msg),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => cw721::execute_approve(deps, env, info, spender, token_id, expires),
+        ExecuteMsg::Revoke { spender, token_id } => {
+            cw721::execute_revoke(deps, env, info, spender, token_id)
+        }
+        ExecuteMsg::ApproveAll { operator, expires } => {
+            cw721::execute_approve_all(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeAll { operator } => cw721::execute_revoke_all(deps, env, info, operator),
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    admin: Option<String>,
+    oracle: Option<String>,
+    oracle_max_staleness: Option<u64>,
+    limits: Option<ValidationLimits>,
+) -> Result<Response, ContractError> {
+    // Load the config
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Check that the sender is the contract admin
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    if let Some(admin) = admin {
+        config.admin = deps.api.addr_validate(&admin)?;
+    }
+    if let Some(oracle) = oracle {
+        config.oracle = Some(deps.api.addr_validate(&oracle)?);
+    }
+    if let Some(oracle_max_staleness) = oracle_max_staleness {
+        config.oracle_max_staleness = oracle_max_staleness;
     }
+    if let Some(limits) = limits {
+        config.limits = limits;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_event(events::config_updated(&env, &config.admin))
+        .add_attribute("action", "update_config"))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_create_organization(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     name: String,
     description: String,
+    handle: Option<String>,
     website: Option<String>,
     metadata: Option<BTreeMap<String, String>>,
 ) -> Result<Response, ContractError> {
+    // Check the organization details against the configured limits
+    let limits = CONFIG.load(deps.storage)?.limits;
+    validation::validate_name(&limits, &name)?;
+    validation::validate_description(&limits, &description)?;
+    if let Some(website) = &website {
+        validation::validate_url(&limits, website)?;
+    }
+    validation::validate_metadata(&limits, &metadata)?;
+
     // Load and save the ID counter
     let organization_id = ORGANIZATION_ID.load(deps.storage)? + 1;
     ORGANIZATION_ID.save(deps.storage, &organization_id)?;
 
     // Create the organization
-    let organization = Organization {
+    let mut organization = Organization {
         owner: info.clone().sender,
         name,
         description,
         website,
         metadata,
+        handle: None,
+        handle_updated_at: None,
     };
+    if handle.is_some() {
+        update_handle(
+            deps.storage,
+            &env,
+            organization_id,
+            &mut organization,
+            handle,
+        )?;
+    }
     ORGANIZATIONS.save(deps.storage, organization_id, &organization)?;
 
     // Load the user's list of organizations
@@ -125,24 +474,222 @@ fn execute_create_organization(
     USER_ORGANIZATIONS.save(deps.storage, info.sender, &user_organizations)?;
 
     Ok(Response::new()
+        .add_event(events::organization_created(
+            &env,
+            organization_id,
+            &organization.owner,
+        ))
         .add_attribute("action", "create_organization")
         .add_attribute("organization_id", organization_id.to_string()))
 }
 
+fn execute_set_verifiers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    verifiers: Vec<String>,
+) -> Result<Response, ContractError> {
+    // Check that the sender is the contract admin
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Validate and save the verifiers
+    let verifiers = verifiers
+        .iter()
+        .map(|verifier| deps.api.addr_validate(verifier))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    VERIFIERS.save(deps.storage, &verifiers)?;
+
+    Ok(Response::new()
+        .add_event(events::verifiers_updated(&env, &verifiers))
+        .add_attribute("action", "set_verifiers"))
+}
+
+// Only the admin and the delegated verifiers can verify organizations
+fn check_verifier(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let verifiers = VERIFIERS.may_load(deps.storage)?.unwrap_or_default();
+    if *sender != config.admin && !verifiers.contains(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn execute_verify_organization(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    proof_url: Option<String>,
+) -> Result<Response, ContractError> {
+    check_verifier(deps.as_ref(), &info.sender)?;
+
+    // Check that the organization exists and is not verified yet
+    ORGANIZATIONS.load(deps.storage, organization_id)?;
+    if VERIFICATIONS.has(deps.storage, organization_id) {
+        return Err(ContractError::AlreadyVerified {});
+    }
+
+    // Validate the proof link
+    if let Some(proof_url) = &proof_url {
+        let limits = CONFIG.load(deps.storage)?.limits;
+        validation::validate_url(&limits, proof_url)?;
+    }
+
+    // Save the verification
+    let verification = Verification {
+        verifier: info.sender,
+        verified_at: env.block.time,
+        proof_url,
+    };
+    VERIFICATIONS.save(deps.storage, organization_id, &verification)?;
+
+    Ok(Response::new()
+        .add_event(events::organization_verified(
+            &env,
+            organization_id,
+            &verification.verifier,
+        ))
+        .add_attribute("action", "verify_organization")
+        .add_attribute("organization_id", organization_id.to_string()))
+}
+
+fn execute_revoke_verification(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+) -> Result<Response, ContractError> {
+    check_verifier(deps.as_ref(), &info.sender)?;
+
+    // Remove the verification
+    if !VERIFICATIONS.has(deps.storage, organization_id) {
+        return Err(ContractError::NotVerified {});
+    }
+    VERIFICATIONS.remove(deps.storage, organization_id);
+
+    Ok(Response::new()
+        .add_event(events::verification_revoked(
+            &env,
+            organization_id,
+            &info.sender,
+        ))
+        .add_attribute("action", "revoke_verification")
+        .add_attribute("organization_id", organization_id.to_string()))
+}
+
+fn execute_set_organization_handle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    handle: Option<String>,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let mut organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the handle was not changed too recently
+    if let Some(updated_at) = organization.handle_updated_at {
+        if updated_at.plus_seconds(HANDLE_CHANGE_COOLDOWN) > env.block.time {
+            return Err(ContractError::HandleCooldown {});
+        }
+    }
+
+    update_handle(
+        deps.storage,
+        &env,
+        organization_id,
+        &mut organization,
+        handle,
+    )?;
+    ORGANIZATIONS.save(deps.storage, organization_id, &organization)?;
+
+    Ok(Response::new()
+        .add_event(events::organization_handle_updated(
+            &env,
+            organization_id,
+            organization.handle.as_deref(),
+        ))
+        .add_attribute("action", "set_organization_handle")
+        .add_attribute("organization_id", organization_id.to_string()))
+}
+
+// Releases the current handle of the organization and claims the new one
+fn update_handle(
+    storage: &mut dyn Storage,
+    env: &Env,
+    organization_id: u32,
+    organization: &mut Organization,
+    handle: Option<String>,
+) -> Result<(), ContractError> {
+    let handle = handle
+        .map(|handle| validation::normalize_handle(&handle))
+        .transpose()?;
+
+    // Check that the new handle is free and not reserved by another organization
+    if let Some(handle) = &handle {
+        if ORGANIZATION_HANDLES
+            .may_load(storage, handle)?
+            .is_some_and(|owner| owner != organization_id)
+        {
+            return Err(ContractError::HandleTaken {});
+        }
+        if let Some((previous, released_at)) = RELEASED_HANDLES.may_load(storage, handle)? {
+            if previous != organization_id
+                && released_at.plus_seconds(HANDLE_RESERVATION_PERIOD) > env.block.time
+            {
+                return Err(ContractError::HandleReserved {});
+            }
+        }
+    }
+
+    // Release the current handle
+    if let Some(current) = organization.handle.take() {
+        ORGANIZATION_HANDLES.remove(storage, &current);
+        RELEASED_HANDLES.save(storage, &current, &(organization_id, env.block.time))?;
+    }
+
+    // Claim the new handle
+    if let Some(handle) = &handle {
+        ORGANIZATION_HANDLES.save(storage, handle, &organization_id)?;
+        RELEASED_HANDLES.remove(storage, handle);
+    }
+    organization.handle = handle;
+    organization.handle_updated_at = Some(env.block.time);
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn execute_create_subscription_plan(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     organization_id: u32,
     name: String,
     description: String,
-    price: Uint128,
+    prices: Vec<Asset>,
+    reference_price: Option<ReferencePrice>,
+    kind: Option<PlanKind>,
+    dunning: Option<DunningPolicy>,
     duration: u8,
     duration_unit: DurationUnit,
     features: Option<Vec<String>>,
     metadata: Option<BTreeMap<String, String>>,
     cancelable: bool,
     refundable: bool,
+    transferable: bool,
+    pausable: bool,
+    max_pause_duration: Option<u64>,
+    max_subscribers: Option<u32>,
+    waitlist: bool,
 ) -> Result<Response, ContractError> {
     // Load the organization
     let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
@@ -152,6 +699,91 @@ fn execute_create_subscription_plan(
         return Err(ContractError::Unauthorized {});
     };
 
+    // Check the plan details against the configured limits
+    let limits = CONFIG.load(deps.storage)?.limits;
+    validation::validate_name(&limits, &name)?;
+    validation::validate_description(&limits, &description)?;
+    validation::validate_features(&limits, &features)?;
+    validation::validate_metadata(&limits, &metadata)?;
+    validation::validate_prices(&limits, &prices)?;
+    if duration == 0 {
+        return Err(ContractError::InvalidDuration {});
+    }
+
+    // Check that every price is valid and accepts a different asset
+    for (index, price) in prices.iter().enumerate() {
+        price.info.validate(deps.api)?;
+        if prices[..index].iter().any(|other| other.info == price.info) {
+            return Err(ContractError::InvalidPrices {});
+        }
+    }
+
+    // Plans priced in a reference currency accept the reference assets instead
+    if let Some(reference_price) = &reference_price {
+        for asset in &reference_price.assets {
+            asset.validate(deps.api)?;
+        }
+        if !prices.is_empty() || reference_price.assets.is_empty() {
+            return Err(ContractError::InvalidPrices {});
+        }
+    }
+
+    // Metered plans charge per unit at fixed rates
+    let kind = kind.unwrap_or(PlanKind::Recurring);
+    match &kind {
+        PlanKind::Metered { .. } => {
+            if prices.is_empty() || reference_price.is_some() {
+                return Err(ContractError::InvalidPrices {});
+            }
+        }
+        PlanKind::Hybrid { overage_prices, .. } => {
+            // Check that every overage rate is valid and charges a different asset
+            for (index, rate) in overage_prices.iter().enumerate() {
+                rate.info.validate(deps.api)?;
+                if overage_prices[..index]
+                    .iter()
+                    .any(|other| other.info == rate.info)
+                {
+                    return Err(ContractError::InvalidPrices {});
+                }
+            }
+            if overage_prices.is_empty() {
+                return Err(ContractError::InvalidPrices {});
+            }
+        }
+        PlanKind::AccessPass { uses } => {
+            if *uses == 0 {
+                return Err(ContractError::InvalidPlanKind {});
+            }
+        }
+        PlanKind::Installments { count, .. } => {
+            if *count == 0 {
+                return Err(ContractError::InvalidPlanKind {});
+            }
+        }
+        PlanKind::Recurring | PlanKind::Lifetime => {}
+    }
+
+    // Only recurring subscriptions keep their remaining time while paused
+    if pausable && kind != PlanKind::Recurring {
+        return Err(ContractError::InvalidPlanKind {});
+    }
+
+    // Check that the renewal retries are increasing and happen within the grace period
+    if let Some(dunning) = &dunning {
+        if dunning
+            .retry_schedule
+            .windows(2)
+            .any(|pair| pair[0] >= pair[1])
+            || dunning
+                .retry_schedule
+                .iter()
+                .any(|offset| *offset == 0 || *offset > dunning.grace_period)
+        {
+            return Err(ContractError::InvalidDunningPolicy {});
+        }
+    }
+
     // Load and save the ID counter
     let subscription_plan_id = SUBSCRIPTION_PLAN_ID.load(deps.storage)? + 1;
     SUBSCRIPTION_PLAN_ID.save(deps.storage, &subscription_plan_id)?;
@@ -161,13 +793,21 @@ fn execute_create_subscription_plan(
         organization_id,
         name,
         description,
-        price,
+        prices,
+        reference_price,
+        kind,
+        dunning,
         duration,
         duration_unit,
         features,
         metadata,
         cancelable,
         refundable,
+        transferable,
+        pausable,
+        max_pause_duration,
+        max_subscribers,
+        waitlist,
     };
     SUBSCRIPTION_PLANS.save(deps.storage, subscription_plan_id, &subscription_plan)?;
 
@@ -185,137 +825,4237 @@ fn execute_create_subscription_plan(
     )?;
 
     Ok(Response::new()
+        .add_event(events::plan_created(
+            &env,
+            subscription_plan_id,
+            organization_id,
+        ))
         .add_attribute("action", "create_subscription_plan")
         .add_attribute("subscription_plan_id", subscription_plan_id.to_string()))
 }
 
-fn execute_subscribe_plan(
+fn execute_receive(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // The sender of the message is the CW20 contract
+    let payment = Asset {
+        info: AssetInfo::Cw20 {
+            address: info.sender,
+        },
+        amount: msg.amount,
+    };
+    let payer = deps.api.addr_validate(&msg.sender)?;
+
+    match from_binary(&msg.msg)? {
+        ReceiveMsg::SubscribePlan {
+            plan_id,
+            beneficiary,
+            coupon,
+            max_price,
+            proof,
+            referrer,
+        } => execute_subscribe_plan(
+            deps,
+            env,
+            payer,
+            Some(payment),
+            plan_id,
+            beneficiary,
+            coupon,
+            max_price,
+            proof,
+            referrer,
+        ),
+        ReceiveMsg::RenewSubscription {
+            subscription_id,
+            max_price,
+        } => {
+            execute_renew_subscription(deps, env, payer, Some(payment), subscription_id, max_price)
+        }
+        ReceiveMsg::ChangePlan {
+            subscription_id,
+            plan_id,
+            max_price,
+            proof,
+        } => execute_change_plan(
+            deps,
+            env,
+            payer,
+            Some(payment),
+            subscription_id,
+            plan_id,
+            max_price,
+            proof,
+        ),
+        ReceiveMsg::DepositCredit { recipient } => {
+            execute_deposit_credit(deps, env, payer, Some(payment), recipient)
+        }
+        ReceiveMsg::TopUp { subscription_id } => {
+            execute_top_up(deps, env, payer, Some(payment), subscription_id)
+        }
+        ReceiveMsg::SubscribeBundle {
+            bundle_id,
+            beneficiary,
+        } => execute_subscribe_bundle(deps, env, payer, Some(payment), bundle_id, beneficiary),
+        ReceiveMsg::RenewBundle {
+            bundle_subscription_id,
+        } => execute_renew_bundle(deps, env, payer, Some(payment), bundle_subscription_id),
+        ReceiveMsg::PayInstallment { subscription_id } => {
+            execute_pay_installment(deps, env, payer, Some(payment), subscription_id)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_subscribe_plan(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
     plan_id: u64,
+    beneficiary: Option<String>,
+    coupon: Option<String>,
+    max_price: Option<Uint128>,
+    proof: Option<Vec<String>>,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
     // Load the subscription plan
     let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
 
-    // Calculate the expiration date based on the duration and duration unit
-    let time_unit = match subscription_plan.duration_unit {
-        DurationUnit::Day => 86400,
-        DurationUnit::Week => 604800,
-        DurationUnit::Month => 2592000,
-        DurationUnit::Year => 31536000,
-    };
-    let expiration = env
-        .block
-        .time
-        .plus_seconds(subscription_plan.duration as u64 * time_unit);
+    // The beneficiary is entitled to the subscription, the payer pays for it
+    let subscriber = maybe_addr(deps.api, beneficiary)?.unwrap_or_else(|| payer.clone());
 
-    // Load and save the subscription id counter
-    let subscription_id = SUBSCRIPTION_ID.load(deps.storage)? + 1;
-    SUBSCRIPTION_ID.save(deps.storage, &subscription_id)?;
+    // Check that the subscriber was not referred by themselves
+    let referrer = maybe_addr(deps.api, referrer)?;
+    if let Some(referrer) = &referrer {
+        if *referrer == subscriber || *referrer == payer {
+            return Err(ContractError::InvalidReferrer {});
+        }
+    }
 
-    // Create the subscription
-    let subscription = Subscription {
-        subscriber: info.clone().sender,
-        plan_id,
-        expiration,
-        canceled: false,
-    };
+    // Check that the subscriber is allowed to subscribe to the plan
+    check_access(deps.as_ref(), &env, plan_id, &subscriber, proof)?;
 
-    // Save the subscription
-    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    // Check that the subscriber holds the tokens required by the plan
+    let holder_discount = check_token_gate(deps.as_ref(), plan_id, &subscriber)?;
 
-    // Update the user's list of subscriptions
-    match USER_SUBSCRIPTIONS.may_load(deps.storage, (info.clone().sender, plan_id))? {
-        // Update an existing subscription
-        Some(existing_subscription_id) => {
-            let existing_subscription =
-                SUBSCRIPTIONS.load(deps.storage, existing_subscription_id)?;
+    // Check if the subscriber already has a subscription, paused and suspended ones included
+    if let Some(existing_subscription_id) =
+        USER_SUBSCRIPTIONS.may_load(deps.storage, (subscriber.clone(), plan_id))?
+    {
+        let existing_subscription = SUBSCRIPTIONS.load(deps.storage, existing_subscription_id)?;
+        if is_held(&existing_subscription, &env) {
+            return Err(ContractError::AlreadySubscribed {});
+        }
 
-            // Check if the subscription is still active
-            if !existing_subscription.canceled || existing_subscription.expiration > env.block.time
-            {
-                return Err(ContractError::AlreadySubscribed {});
-            }
+        // The replaced subscription gives up its seat
+        release_seat(deps.storage, existing_subscription_id)?;
+    }
 
-            // Update the existing subscription
-            USER_SUBSCRIPTIONS.save(
-                deps.storage,
-                (info.clone().sender, plan_id),
-                &subscription_id,
-            )?
+    // Check that the plan has room for the subscriber
+    check_capacity(deps.storage, &subscription_plan, plan_id, &subscriber)?;
+
+    // Metered plans are paid from a prepaid balance instead of upfront
+    if let PlanKind::Metered { .. } = subscription_plan.kind {
+        return subscribe_metered_plan(
+            deps,
+            env,
+            payer,
+            payment,
+            plan_id,
+            subscription_plan,
+            subscriber,
+            coupon,
+            referrer,
+        );
+    }
+
+    // Calculate the expiration date based on the duration and duration unit
+    let expiration = match subscription_plan.kind {
+        // Lifetime subscriptions and access passes do not expire over time
+        PlanKind::Lifetime | PlanKind::AccessPass { .. } => NEVER_EXPIRES,
+        _ => env
+            .block
+            .time
+            .plus_seconds(plan_period_seconds(&subscription_plan)),
+    };
+    let remaining_uses = match subscription_plan.kind {
+        PlanKind::AccessPass { uses } => Some(uses),
+        _ => None,
+    };
+    let grace_period = match subscription_plan.kind {
+        PlanKind::Recurring | PlanKind::Hybrid { .. } => subscription_plan
+            .dunning
+            .as_ref()
+            .map(|dunning| dunning.grace_period)
+            .unwrap_or_default(),
+        _ => 0,
+    };
+
+    // Select the price of the asset the payer pays with
+    let payment_asset = payment_asset(deps.storage, &payer, &subscription_plan, payment.as_ref())?;
+    let plan_price = resolve_plan_price(
+        deps.as_ref(),
+        &env,
+        &subscription_plan,
+        payment_asset.as_ref(),
+    )?;
+
+    // Redeem the coupon and apply its discount to the plan price
+    let mut applied_coupon = coupon
+        .map(|code| {
+            redeem_coupon(
+                deps.storage,
+                &env,
+                subscription_plan.organization_id,
+                plan_id,
+                &subscriber,
+                &code,
+            )
+        })
+        .transpose()?;
+    let price = apply_coupon(&mut applied_coupon, plan_price)?;
+
+    // Token holders get the holder discount on the first period
+    let price = match (holder_discount, price) {
+        (Some(percent), Some(price)) => Some(Discount::Percent(percent).apply(&price)?),
+        (_, price) => price,
+    };
+
+    // Hybrid plans charge the overage from a prepaid balance, preferably in the payment asset
+    let balance = match &subscription_plan.kind {
+        PlanKind::Hybrid { overage_prices, .. } => {
+            let rate = overage_prices
+                .iter()
+                .find(|rate| Some(&rate.info) == payment.as_ref().map(|payment| &payment.info))
+                .unwrap_or(&overage_prices[0]);
+            Some(Asset {
+                info: rate.info.clone(),
+                amount: Uint128::zero(),
+            })
         }
-        // Create a new subscription
-        None => USER_SUBSCRIPTIONS.save(
-            deps.storage,
-            (info.clone().sender, plan_id),
-            &subscription_id,
-        )?,
+        // Renewals and installments can be paid from a prepaid balance in the price asset
+        PlanKind::Recurring | PlanKind::Installments { .. } => price.as_ref().map(|price| Asset {
+            info: price.info.clone(),
+            amount: Uint128::zero(),
+        }),
+        _ => None,
+    };
+
+    // Installment plans split the price over the duration and collect the first installment
+    let mut installments = match subscription_plan.kind {
+        PlanKind::Installments {
+            count,
+            grace_period,
+        } => price.as_ref().map(|price| InstallmentSchedule {
+            total: price.clone(),
+            count,
+            paid_installments: 0,
+            interval: plan_period_seconds(&subscription_plan) / count as u64,
+            grace_period,
+        }),
+        _ => None,
+    };
+    let due = match &installments {
+        Some(installments) => installments.next_installment(),
+        None => price.clone(),
+    };
+
+    // The payer's credit covers the part of the amount due the payment does not
+    let credit = credit_to_spend(deps.storage, &payer, due.as_ref(), payment.as_ref())?;
+    let (uncovered, max_price) = without_credit(due.as_ref(), credit.as_ref(), max_price);
+
+    // Check that the payment covers the rest of the amount due and collect it
+    let excess = settle_payment(&subscription_plan, payment, uncovered.as_ref(), max_price)?;
+    if let Some(installments) = &mut installments {
+        installments.paid_installments += 1;
     }
 
-    // Update the subscription plan's list of subscriptions
-    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.save(deps.storage, (plan_id, info.sender), &subscription_id)?;
+    // Create the subscription
+    let subscription = Subscription {
+        subscriber: subscriber.clone(),
+        payer: payer.clone(),
+        plan_id,
+        started: env.block.time,
+        expiration,
+        canceled: false,
+        status: SubscriptionStatus::Active,
+        grace_period,
+        renewal_attempts: 0,
+        paused_at: None,
+        price: price.clone(),
+        coupon: applied_coupon,
+        balance,
+        remaining_uses,
+        installments,
+        referrer,
+    };
+    let subscription_id = save_new_subscription(deps.storage, &subscription)?;
+    if let Some(due) = &due {
+        add_subscription_revenue(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &subscription,
+            due,
+            true,
+        )?;
+    }
+    let credit_events = credit
+        .map(|credit| {
+            deduct_credit(
+                deps.storage,
+                &env,
+                &payer,
+                &credit,
+                "subscription",
+                Some(subscription_id),
+            )
+        })
+        .transpose()?;
 
-    Ok(Response::new()
+    let organization_id = subscription_plan.organization_id;
+    let mut response = Response::new()
+        .add_event(events::subscription_created(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+        ))
+        .add_events(credit_events)
         .add_attribute("action", "subscribe_plan")
         .add_attribute(
             "organization_id",
             subscription_plan.organization_id.to_string(),
         )
         .add_attribute("subscription_plan_id", plan_id.to_string())
-        .add_attribute("subscription_id", subscription_id.to_string()))
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("token_id", subscription_id.to_string())
+        .add_attribute("subscriber", subscriber)
+        .add_attribute("payer", &payer);
+    response = response.add_submessages(hook_messages(
+        deps.storage,
+        organization_id,
+        SubscriptionHookMsg::Created {
+            subscription_id,
+            plan_id,
+            subscriber: subscription.subscriber.clone(),
+            expiration: subscription.expiration,
+        },
+    )?);
+    if let Some(due) = due.filter(|due| !due.amount.is_zero()) {
+        response = response.add_event(events::payment_received(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+            &payer,
+            &due,
+            "subscription",
+        ));
+    }
+    if let Some(price) = price {
+        response = response.add_attribute("price", price.to_string());
+    }
+    if let Some(excess) = excess {
+        response = response.add_message(excess.transfer_msg(&payer)?);
+    }
+
+    Ok(response)
+}
+
+fn execute_renew_subscription(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
+    subscription_id: u64,
+    max_price: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Check that the subscription is not canceled
+    if subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+
+    // Bundled subscriptions are managed through their bundle
+    if BUNDLED_SUBSCRIPTIONS.has(deps.storage, subscription_id) {
+        return Err(ContractError::BundledSubscription {});
+    };
+
+    // Check that the subscription is not paused
+    if subscription.status == SubscriptionStatus::Paused {
+        return Err(ContractError::SubscriptionPaused {});
+    };
+
+    // Metered subscriptions are topped up and one-time purchases are not renewed
+    if let PlanKind::Metered { .. }
+    | PlanKind::Lifetime
+    | PlanKind::AccessPass { .. }
+    | PlanKind::Installments { .. } = subscription_plan.kind
+    {
+        return Err(ContractError::NotRenewable {});
+    };
+
+    // Check that the subscription was not replaced by a newer one
+    let current_subscription_id = USER_SUBSCRIPTIONS.may_load(
+        deps.storage,
+        (subscription.subscriber.clone(), subscription.plan_id),
+    )?;
+    if current_subscription_id != Some(subscription_id) {
+        return Err(ContractError::NotRenewable {});
+    };
+
+    // Past due subscriptions continue from their expiration, lapsed ones start anew
+    let period = plan_period_seconds(&subscription_plan);
+    let start = if env.block.time > grace_end(&subscription) {
+        env.block.time
+    } else {
+        subscription.expiration
+    };
+
+    // Only one period can be paid in advance
+    if start.seconds().saturating_sub(env.block.time.seconds()) > period {
+        return Err(ContractError::NotRenewable {});
+    };
+
+    // Lapsed subscriptions gave up their seat and need a free one
+    if !SEATS.has(deps.storage, subscription_id) {
+        check_capacity(
+            deps.storage,
+            &subscription_plan,
+            subscription.plan_id,
+            &subscription.subscriber,
+        )?;
+        occupy_seat(deps.storage, subscription.plan_id, subscription_id)?;
+    }
+
+    // Select the price of the asset the payer pays with
+    let payment_asset = payment_asset(deps.storage, &payer, &subscription_plan, payment.as_ref())?;
+    let plan_price = resolve_plan_price(
+        deps.as_ref(),
+        &env,
+        &subscription_plan,
+        payment_asset.as_ref(),
+    )?;
+    let price = apply_coupon(&mut subscription.coupon, plan_price)?;
+
+    // The payer's credit covers the part of the price the payment does not
+    let credit = credit_to_spend(deps.storage, &payer, price.as_ref(), payment.as_ref())?;
+    let (uncovered, max_price) = without_credit(price.as_ref(), credit.as_ref(), max_price);
+
+    // Check that the payment covers the rest of the price and collect it
+    let excess = settle_payment(&subscription_plan, payment, uncovered.as_ref(), max_price)?;
+    let credit_events = credit
+        .map(|credit| {
+            deduct_credit(
+                deps.storage,
+                &env,
+                &payer,
+                &credit,
+                "renewal",
+                Some(subscription_id),
+            )
+        })
+        .transpose()?;
+
+    // Extend the subscription by one period, whoever renews does not become the payer
+    subscription.expiration = start.plus_seconds(period);
+    subscription.status = SubscriptionStatus::Active;
+    subscription.renewal_attempts = 0;
+    subscription.price = price.clone();
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    if let Some(price) = &price {
+        add_subscription_revenue(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &subscription,
+            price,
+            false,
+        )?;
+    }
+
+    // Restore the subscription in the plan's list if it expired
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.save(
+        deps.storage,
+        (subscription.plan_id, subscription.subscriber.clone()),
+        &subscription_id,
+    )?;
+
+    let organization_id = subscription_plan.organization_id;
+    let mut response = Response::new()
+        .add_event(events::subscription_renewed(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+        ))
+        .add_events(credit_events)
+        .add_submessages(hook_messages(
+            deps.storage,
+            organization_id,
+            SubscriptionHookMsg::Renewed {
+                subscription_id,
+                plan_id: subscription.plan_id,
+                subscriber: subscription.subscriber.clone(),
+                expiration: subscription.expiration,
+            },
+        )?)
+        .add_attribute("action", "renew_subscription")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("payer", &payer)
+        .add_attribute("expiration", subscription.expiration.to_string());
+    if let Some(price) = price {
+        if !price.amount.is_zero() {
+            response = response.add_event(events::payment_received(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+                &payer,
+                &price,
+                "renewal",
+            ));
+        }
+        response = response.add_attribute("price", price.to_string());
+    }
+    if let Some(excess) = excess {
+        response = response.add_message(excess.transfer_msg(&payer)?);
+    }
+
+    Ok(response)
+}
+
+// Moves a recurring subscription to another recurring plan of the same organization, the unused
+// part of the current period is refunded to the payer's credit and a new period of the new plan
+// starts right away
+#[allow(clippy::too_many_arguments)]
+fn execute_change_plan(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
+    subscription_id: u64,
+    plan_id: u64,
+    max_price: Option<Uint128>,
+    proof: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the current and the new subscription plan
+    let old_plan_id = subscription.plan_id;
+    let old_plan = SUBSCRIPTION_PLANS.load(deps.storage, old_plan_id)?;
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+
+    // Check that the sender is the subscriber
+    if payer != subscription.subscriber {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Bundled subscriptions are managed through their bundle
+    if BUNDLED_SUBSCRIPTIONS.has(deps.storage, subscription_id) {
+        return Err(ContractError::BundledSubscription {});
+    };
+
+    // Check that the subscription is neither canceled, paused nor expired
+    if subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+    if subscription.status == SubscriptionStatus::Paused {
+        return Err(ContractError::SubscriptionPaused {});
+    };
+    if !is_active(&subscription, &env) {
+        return Err(ContractError::AlreadyExpired {});
+    };
+
+    // Only recurring subscriptions move to another recurring plan of the same organization
+    if plan_id == old_plan_id
+        || old_plan.kind != PlanKind::Recurring
+        || subscription_plan.kind != PlanKind::Recurring
+        || subscription_plan.organization_id != old_plan.organization_id
+    {
+        return Err(ContractError::InvalidPlanChange {});
+    };
+
+    // Check that the subscriber is allowed to subscribe to the new plan
+    let subscriber = subscription.subscriber.clone();
+    check_access(deps.as_ref(), &env, plan_id, &subscriber, proof)?;
+
+    // Check that the subscriber holds the tokens required by the new plan
+    let holder_discount = check_token_gate(deps.as_ref(), plan_id, &subscriber)?;
+
+    // Check if the subscriber already has a subscription to the new plan
+    if let Some(existing_subscription_id) =
+        USER_SUBSCRIPTIONS.may_load(deps.storage, (subscriber.clone(), plan_id))?
+    {
+        let existing_subscription = SUBSCRIPTIONS.load(deps.storage, existing_subscription_id)?;
+        if is_held(&existing_subscription, &env) {
+            return Err(ContractError::AlreadySubscribed {});
+        }
+
+        // The replaced subscription gives up its seat
+        release_seat(deps.storage, existing_subscription_id)?;
+    }
+
+    // Check that the new plan has room for the subscriber
+    check_capacity(deps.storage, &subscription_plan, plan_id, &subscriber)?;
+
+    // Credit the unused part of the current period and the prepaid balance to the payer
+    let organization_id = subscription_plan.organization_id;
+    let refund = refund_escrow(
+        deps.storage,
+        &env,
+        &old_plan,
+        subscription_id,
+        &subscription,
+    )?;
+    let balance = withdraw_balance(&mut subscription);
+    let mut credit_events = vec![];
+    for (refund, credit_reason, reason) in [
+        (refund, "refund", "unused_time"),
+        (balance, "balance", "balance"),
+    ] {
+        if let Some(refund) = refund.filter(|refund| !refund.amount.is_zero()) {
+            credit_events.push(add_credit(
+                deps.storage,
+                &env,
+                &subscription.payer,
+                &refund,
+                credit_reason,
+                Some(subscription_id),
+            )?);
+            credit_events.push(events::refund_issued(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+                &refund,
+                reason,
+            ));
+        }
+    }
+
+    // Select the price of the asset the payer pays with, coupons of the previous plan do not
+    // carry over and token holders get the holder discount on the first period
+    let payment_asset = payment_asset(deps.storage, &payer, &subscription_plan, payment.as_ref())?;
+    let plan_price = resolve_plan_price(
+        deps.as_ref(),
+        &env,
+        &subscription_plan,
+        payment_asset.as_ref(),
+    )?;
+    let price = match (holder_discount, plan_price) {
+        (Some(percent), Some(price)) => Some(Discount::Percent(percent).apply(&price)?),
+        (_, price) => price,
+    };
+
+    // The payer's credit, refunds included, covers the part of the price the payment does not
+    let credit = credit_to_spend(deps.storage, &payer, price.as_ref(), payment.as_ref())?;
+    let (uncovered, max_price) = without_credit(price.as_ref(), credit.as_ref(), max_price);
+
+    // Check that the payment covers the rest of the price and collect it
+    let excess = settle_payment(&subscription_plan, payment, uncovered.as_ref(), max_price)?;
+    if let Some(credit) = credit {
+        credit_events.push(deduct_credit(
+            deps.storage,
+            &env,
+            &payer,
+            &credit,
+            "plan_change",
+            Some(subscription_id),
+        )?);
+    }
+
+    // Move the subscription indexes and the seat to the new plan
+    USER_SUBSCRIPTIONS.remove(deps.storage, (subscriber.clone(), old_plan_id));
+    USER_SUBSCRIPTIONS.save(
+        deps.storage,
+        (subscriber.clone(), plan_id),
+        &subscription_id,
+    )?;
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.remove(deps.storage, (old_plan_id, subscriber.clone()));
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.save(
+        deps.storage,
+        (plan_id, subscriber.clone()),
+        &subscription_id,
+    )?;
+    release_seat(deps.storage, subscription_id)?;
+    occupy_seat(deps.storage, plan_id, subscription_id)?;
+
+    // Start a new period of the new plan, the sender becomes the payer
+    subscription.plan_id = plan_id;
+    subscription.payer = payer.clone();
+    subscription.started = env.block.time;
+    subscription.expiration = env
+        .block
+        .time
+        .plus_seconds(plan_period_seconds(&subscription_plan));
+    subscription.status = SubscriptionStatus::Active;
+    subscription.grace_period = subscription_plan
+        .dunning
+        .as_ref()
+        .map(|dunning| dunning.grace_period)
+        .unwrap_or_default();
+    subscription.renewal_attempts = 0;
+    subscription.price = price.clone();
+    subscription.coupon = None;
+    subscription.balance = price.as_ref().map(|price| Asset {
+        info: price.info.clone(),
+        amount: Uint128::zero(),
+    });
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    if let Some(price) = &price {
+        add_subscription_revenue(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &subscription,
+            price,
+            false,
+        )?;
+    }
+
+    let mut response = Response::new()
+        .add_event(events::plan_changed(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+            old_plan_id,
+        ))
+        .add_events(credit_events)
+        .add_submessages(hook_messages(
+            deps.storage,
+            organization_id,
+            SubscriptionHookMsg::PlanChanged {
+                subscription_id,
+                old_plan_id,
+                new_plan_id: plan_id,
+                subscriber: subscriber.clone(),
+            },
+        )?)
+        .add_attribute("action", "change_plan")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("old_plan_id", old_plan_id.to_string())
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_attribute("payer", &payer)
+        .add_attribute("expiration", subscription.expiration.to_string());
+    if let Some(price) = price {
+        if !price.amount.is_zero() {
+            response = response.add_event(events::payment_received(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+                &payer,
+                &price,
+                "plan_change",
+            ));
+        }
+        response = response.add_attribute("price", price.to_string());
+    }
+    if let Some(excess) = excess {
+        response = response.add_message(excess.transfer_msg(&payer)?);
+    }
+
+    Ok(response)
+}
+
+// Subscribes to a metered plan, the payment is deposited as the prepaid balance
+#[allow(clippy::too_many_arguments)]
+fn subscribe_metered_plan(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
+    plan_id: u64,
+    subscription_plan: SubscriptionPlan,
+    subscriber: Addr,
+    coupon: Option<String>,
+    referrer: Option<Addr>,
+) -> Result<Response, ContractError> {
+    // Coupons only discount upfront prices
+    if coupon.is_some() {
+        return Err(ContractError::CouponNotApplicable {});
+    };
+
+    // The payment has to be in one of the assets the plan charges in
+    let balance = match payment {
+        Some(payment) => {
+            if !subscription_plan
+                .prices
+                .iter()
+                .any(|rate| rate.info == payment.info)
+            {
+                return Err(ContractError::InvalidPayment {});
+            }
+            payment
+        }
+        None => Asset {
+            info: subscription_plan.prices[0].info.clone(),
+            amount: Uint128::zero(),
+        },
+    };
+
+    // Create the subscription, it stays active until it is canceled
+    let subscription = Subscription {
+        subscriber: subscriber.clone(),
+        payer: payer.clone(),
+        plan_id,
+        started: env.block.time,
+        expiration: NEVER_EXPIRES,
+        canceled: false,
+        status: SubscriptionStatus::Active,
+        grace_period: 0,
+        renewal_attempts: 0,
+        paused_at: None,
+        price: None,
+        coupon: None,
+        balance: Some(balance.clone()),
+        remaining_uses: None,
+        installments: None,
+        referrer,
+    };
+    let subscription_id = save_new_subscription(deps.storage, &subscription)?;
+
+    let organization_id = subscription_plan.organization_id;
+    let mut response = Response::new()
+        .add_event(events::subscription_created(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+        ))
+        .add_attribute("action", "subscribe_plan")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("token_id", subscription_id.to_string())
+        .add_attribute("subscriber", subscriber)
+        .add_attribute("payer", &payer)
+        .add_attribute("balance", balance.to_string());
+    response = response.add_submessages(hook_messages(
+        deps.storage,
+        organization_id,
+        SubscriptionHookMsg::Created {
+            subscription_id,
+            plan_id,
+            subscriber: subscription.subscriber.clone(),
+            expiration: subscription.expiration,
+        },
+    )?);
+    if !balance.amount.is_zero() {
+        response = response.add_event(events::payment_received(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+            &payer,
+            &balance,
+            "top_up",
+        ));
+    }
+
+    Ok(response)
+}
+
+// Checks that the subscriber passes the access control of the plan
+fn check_access(
+    deps: Deps,
+    env: &Env,
+    plan_id: u64,
+    subscriber: &Addr,
+    proof: Option<Vec<String>>,
+) -> Result<(), ContractError> {
+    match PLAN_ACCESS.may_load(deps.storage, plan_id)? {
+        None | Some(PlanAccess::Public) => Ok(()),
+        Some(PlanAccess::Allowlist) => {
+            if !PLAN_ALLOWLIST.has(deps.storage, (plan_id, subscriber.clone())) {
+                return Err(ContractError::NotAllowed {});
+            }
+            Ok(())
+        }
+        Some(PlanAccess::MerkleAllowlist { root }) => {
+            let proof = proof.ok_or(ContractError::NotAllowed {})?;
+            if !verify_merkle_proof(subscriber, &proof, &root)? {
+                return Err(ContractError::NotAllowed {});
+            }
+            Ok(())
+        }
+        Some(PlanAccess::Prerequisite {
+            plan_id: prerequisite_plan_id,
+        }) => {
+            let active = USER_SUBSCRIPTIONS
+                .may_load(deps.storage, (subscriber.clone(), prerequisite_plan_id))?
+                .map(|subscription_id| SUBSCRIPTIONS.load(deps.storage, subscription_id))
+                .transpose()?
+                .is_some_and(|subscription| is_active(&subscription, env));
+            if !active {
+                return Err(ContractError::PrerequisiteNotMet {});
+            }
+            Ok(())
+        }
+    }
+}
+
+// Checks that the subscriber holds the tokens required by the plan and returns the holder discount
+fn check_token_gate(
+    deps: Deps,
+    plan_id: u64,
+    subscriber: &Addr,
+) -> Result<Option<u8>, ContractError> {
+    let token_gate = match TOKEN_GATES.may_load(deps.storage, plan_id)? {
+        Some(token_gate) => token_gate,
+        None => return Ok(None),
+    };
+
+    let is_holder = match token_gate.requirement {
+        TokenRequirement::Cw20 {
+            address,
+            min_balance,
+        } => {
+            let res: Cw20BalanceResponse = deps.querier.query_wasm_smart(
+                address,
+                &Cw20QueryMsg::Balance {
+                    address: subscriber.to_string(),
+                },
+            )?;
+            res.balance >= min_balance
+        }
+        TokenRequirement::Cw721 { address } => {
+            let res: TokensResponse = deps.querier.query_wasm_smart(
+                address,
+                &Cw721QueryMsg::Tokens {
+                    owner: subscriber.to_string(),
+                    start_after: None,
+                    limit: Some(1),
+                },
+            )?;
+            !res.tokens.is_empty()
+        }
+    };
+    if !is_holder {
+        return Err(ContractError::TokenGateNotMet {});
+    }
+
+    Ok(token_gate.holder_discount)
+}
+
+// Hashes the sorted pairs from the address leaf up to the root
+fn verify_merkle_proof(
+    subscriber: &Addr,
+    proof: &[String],
+    root: &str,
+) -> Result<bool, ContractError> {
+    let mut hash: [u8; 32] = Sha256::digest(subscriber.as_bytes()).into();
+    for sibling in proof {
+        let mut sibling_hash = [0u8; 32];
+        hex::decode_to_slice(sibling, &mut sibling_hash)
+            .map_err(|_| ContractError::InvalidProof {})?;
+
+        let (first, second) = if hash <= sibling_hash {
+            (hash, sibling_hash)
+        } else {
+            (sibling_hash, hash)
+        };
+        hash = Sha256::digest([first, second].concat()).into();
+    }
+
+    Ok(hex::encode(hash) == root)
+}
+
+// Checks that the plan has a free seat, users admitted from the waitlist take their reserved seat
+fn check_capacity(
+    storage: &mut dyn Storage,
+    subscription_plan: &SubscriptionPlan,
+    plan_id: u64,
+    subscriber: &Addr,
+) -> Result<(), ContractError> {
+    let max_subscribers = match subscription_plan.max_subscribers {
+        Some(max_subscribers) => max_subscribers,
+        None => return Ok(()),
+    };
+    if WAITLIST_ADMISSIONS.has(storage, (plan_id, subscriber.clone())) {
+        WAITLIST_ADMISSIONS.remove(storage, (plan_id, subscriber.clone()));
+        return Ok(());
+    }
+    if free_seats(storage, plan_id, max_subscribers)? == 0 {
+        return Err(ContractError::PlanFull {});
+    }
+    Ok(())
+}
+
+// Seats that are neither taken by a subscriber nor reserved for an admitted user
+fn free_seats(storage: &dyn Storage, plan_id: u64, max_subscribers: u32) -> StdResult<u32> {
+    let active_subscribers = ACTIVE_SUBSCRIBERS
+        .may_load(storage, plan_id)?
+        .unwrap_or_default();
+    let reserved_seats = WAITLIST_ADMISSIONS
+        .prefix(plan_id)
+        .keys(storage, None, None, Order::Ascending)
+        .count() as u32;
+    Ok(max_subscribers.saturating_sub(active_subscribers + reserved_seats))
+}
+
+fn occupy_seat(storage: &mut dyn Storage, plan_id: u64, subscription_id: u64) -> StdResult<()> {
+    SEATS.save(storage, subscription_id, &plan_id)?;
+    ACTIVE_SUBSCRIBERS.update(storage, plan_id, |count| -> StdResult<u32> {
+        Ok(count.unwrap_or_default() + 1)
+    })?;
+    Ok(())
+}
+
+fn release_seat(storage: &mut dyn Storage, subscription_id: u64) -> StdResult<()> {
+    if let Some(plan_id) = SEATS.may_load(storage, subscription_id)? {
+        SEATS.remove(storage, subscription_id);
+        ACTIVE_SUBSCRIBERS.update(storage, plan_id, |count| -> StdResult<u32> {
+            Ok(count.unwrap_or_default().saturating_sub(1))
+        })?;
+    }
+    Ok(())
+}
+
+fn save_new_subscription(storage: &mut dyn Storage, subscription: &Subscription) -> StdResult<u64> {
+    // Load and save the subscription id counter
+    let subscription_id = SUBSCRIPTION_ID.load(storage)? + 1;
+    SUBSCRIPTION_ID.save(storage, &subscription_id)?;
+
+    // Save the subscription and take a seat of the plan
+    SUBSCRIPTIONS.save(storage, subscription_id, subscription)?;
+    occupy_seat(storage, subscription.plan_id, subscription_id)?;
+
+    // Update the user's list of subscriptions and subscription tokens
+    USER_SUBSCRIPTIONS.save(
+        storage,
+        (subscription.subscriber.clone(), subscription.plan_id),
+        &subscription_id,
+    )?;
+    OWNER_TOKENS.save(
+        storage,
+        (subscription.subscriber.clone(), subscription_id),
+        &true,
+    )?;
+
+    // Update the subscription plan's list of subscriptions
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.save(
+        storage,
+        (subscription.plan_id, subscription.subscriber.clone()),
+        &subscription_id,
+    )?;
+
+    Ok(subscription_id)
+}
+
+fn execute_top_up(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Check that the subscription is not canceled
+    if subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+
+    // Check that the payment is in the asset of the prepaid balance
+    let mut balance = subscription
+        .balance
+        .take()
+        .ok_or(ContractError::NotMetered {})?;
+    let payment = payment
+        .filter(|payment| payment.info == balance.info && !payment.amount.is_zero())
+        .ok_or(ContractError::InvalidPayment {})?;
+
+    // Add the payment to the prepaid balance
+    balance.amount += payment.amount;
+    subscription.balance = Some(balance.clone());
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+
+    Ok(Response::new()
+        .add_event(events::payment_received(
+            &env,
+            subscription_plan.organization_id,
+            subscription_id,
+            &subscription,
+            &payer,
+            &payment,
+            "top_up",
+        ))
+        .add_attribute("action", "top_up")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("amount", payment.to_string())
+        .add_attribute("balance", balance.to_string()))
+}
+
+fn execute_pay_installment(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Check that the subscription is not canceled
+    if subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+
+    // Check that the payment matches the next installment
+    let installments = subscription
+        .installments
+        .as_mut()
+        .ok_or(ContractError::NoInstallmentDue {})?;
+    let installment = installments
+        .next_installment()
+        .ok_or(ContractError::NoInstallmentDue {})?;
+    if payment.as_ref() != Some(&installment) {
+        return Err(ContractError::InvalidPayment {});
+    }
+
+    // Collect the installment and lift the suspension once nothing is overdue
+    installments.paid_installments += 1;
+    update_installment_status(&mut subscription, &env);
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    add_subscription_revenue(
+        deps.storage,
+        &env,
+        &subscription_plan,
+        subscription_id,
+        &subscription,
+        &installment,
+        false,
+    )?;
+
+    Ok(Response::new()
+        .add_event(events::payment_received(
+            &env,
+            subscription_plan.organization_id,
+            subscription_id,
+            &subscription,
+            &payer,
+            &installment,
+            "installment",
+        ))
+        .add_attribute("action", "pay_installment")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("installment", installment.to_string()))
+}
+
+// Pays the due installments from the prepaid balance, returns the collected amount
+fn collect_installments(
+    storage: &mut dyn Storage,
+    subscription_plan: &SubscriptionPlan,
+    subscription_id: u64,
+    subscription: &mut Subscription,
+    env: &Env,
+) -> Result<Option<Asset>, ContractError> {
+    let (Some(installments), Some(balance)) = (
+        subscription.installments.as_mut(),
+        subscription.balance.as_mut(),
+    ) else {
+        return Ok(None);
+    };
+
+    let mut collected = Asset {
+        info: balance.info.clone(),
+        amount: Uint128::zero(),
+    };
+    while let (Some(installment), Some(due)) = (
+        installments.next_installment(),
+        installments.next_due(subscription.started),
+    ) {
+        if due > env.block.time || balance.amount < installment.amount {
+            break;
+        }
+        balance.amount -= installment.amount;
+        collected.amount += installment.amount;
+        installments.paid_installments += 1;
+    }
+
+    // The collected installments become revenue of the organization
+    if !collected.amount.is_zero() {
+        add_subscription_revenue(
+            storage,
+            env,
+            subscription_plan,
+            subscription_id,
+            subscription,
+            &collected,
+            false,
+        )?;
+    }
+
+    Ok(Some(collected))
+}
+
+// Suspends subscriptions with an overdue installment and reactivates paid up ones, lapsed
+// subscriptions stay lapsed
+fn update_installment_status(subscription: &mut Subscription, env: &Env) {
+    if subscription.status == SubscriptionStatus::Lapsed {
+        return;
+    }
+    if let Some(installments) = &subscription.installments {
+        subscription.status = if installments.is_overdue(subscription.started, env.block.time) {
+            SubscriptionStatus::Suspended
+        } else {
+            SubscriptionStatus::Active
+        };
+    }
+}
+
+fn execute_report_usage(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    subscription_id: u64,
+    units: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Check that the sender is the organization owner or a usage reporter
+    let organization = ORGANIZATIONS.load(deps.storage, subscription_plan.organization_id)?;
+    let reporters = USAGE_REPORTERS
+        .may_load(deps.storage, subscription_plan.organization_id)?
+        .unwrap_or_default();
+    if info.sender != organization.owner && !reporters.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the subscription plan is billed by usage
+    let usage_cap = match subscription_plan.kind {
+        PlanKind::Metered { usage_cap } => usage_cap,
+        PlanKind::Hybrid { .. } | PlanKind::AccessPass { .. } => None,
+        _ => return Err(ContractError::NotMetered {}),
+    };
+
+    // Check that the subscription is not canceled
+    if subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+
+    // Check that the subscription is not expired
+    if env.block.time > subscription.expiration {
+        return Err(ContractError::AlreadyExpired {});
+    };
+
+    // Access passes consume their uses instead of recording usage
+    if let PlanKind::AccessPass { .. } = subscription_plan.kind {
+        let remaining_uses = u32::try_from(units)
+            .ok()
+            .and_then(|units| {
+                subscription
+                    .remaining_uses
+                    .unwrap_or_default()
+                    .checked_sub(units)
+            })
+            .ok_or(ContractError::InsufficientUses {})?;
+        subscription.remaining_uses = Some(remaining_uses);
+        SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+
+        return Ok(Response::new()
+            .add_event(events::usage_reported(
+                &env,
+                subscription_plan.organization_id,
+                subscription_id,
+                &subscription,
+                units,
+            ))
+            .add_attribute("action", "report_usage")
+            .add_attribute("subscription_id", subscription_id.to_string())
+            .add_attribute("units", units.to_string())
+            .add_attribute("remaining_uses", remaining_uses.to_string()));
+    }
+
+    // Check that the usage of the current period stays within the cap
+    let period = usage_period(&subscription_plan, &subscription, &env);
+    let mut usage = USAGE
+        .may_load(deps.storage, (subscription_id, period))?
+        .unwrap_or(PeriodUsage {
+            units: 0,
+            cost: Uint128::zero(),
+            unpaid: Uint128::zero(),
+            settled: false,
+        });
+    usage.units = usage.units.saturating_add(units);
+    if usage_cap.is_some_and(|usage_cap| usage.units > usage_cap) {
+        return Err(ContractError::UsageCapExceeded {});
+    }
+
+    let mut response = Response::new()
+        .add_event(events::usage_reported(
+            &env,
+            subscription_plan.organization_id,
+            subscription_id,
+            &subscription,
+            units,
+        ))
+        .add_attribute("action", "report_usage")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("period", period.to_string())
+        .add_attribute("units", units.to_string());
+
+    // Metered plans charge the units right away, hybrid plans when the period is closed
+    if let PlanKind::Metered { .. } = subscription_plan.kind {
+        // Charge the units at the rate of the prepaid balance asset
+        let mut balance = subscription
+            .balance
+            .take()
+            .ok_or(ContractError::NotMetered {})?;
+        let rate = subscription_plan
+            .prices
+            .iter()
+            .find(|rate| rate.info == balance.info)
+            .ok_or(ContractError::InvalidPayment {})?;
+        let cost = Asset {
+            info: balance.info.clone(),
+            amount: rate
+                .amount
+                .checked_mul(Uint128::from(units))
+                .map_err(StdError::overflow)?,
+        };
+        balance.amount = balance
+            .amount
+            .checked_sub(cost.amount)
+            .map_err(|_| ContractError::InsufficientBalance {})?;
+        usage.cost += cost.amount;
+        usage.settled = true;
+
+        // The charged amount becomes revenue of the organization
+        subscription.balance = Some(balance.clone());
+        SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+        add_subscription_revenue(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &subscription,
+            &cost,
+            false,
+        )?;
+
+        response = response
+            .add_event(events::balance_charged(
+                &env,
+                subscription_plan.organization_id,
+                subscription_id,
+                &subscription,
+                &cost,
+                "usage",
+            ))
+            .add_attribute("cost", cost.to_string())
+            .add_attribute("balance", balance.to_string());
+    }
+    USAGE.save(deps.storage, (subscription_id, period), &usage)?;
+
+    Ok(response)
+}
+
+fn execute_process_subscription(
+    mut deps: DepsMut,
+    env: Env,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Resume subscriptions paused for longer than the maximum pause length
+    let mut events = vec![];
+    if let Some(resumed_at) = pause_end(&subscription_plan, &subscription)
+        .filter(|resumed_at| *resumed_at <= env.block.time)
+    {
+        resume_subscription(&mut subscription, resumed_at);
+        events.push(events::subscription_resumed(
+            &env,
+            subscription_plan.organization_id,
+            subscription_id,
+            &subscription,
+        ));
+    }
+
+    // Close the elapsed usage periods and charge their overage before the next period is paid
+    let period = usage_period(&subscription_plan, &subscription, &env);
+    let mut overage = settle_overage(
+        deps.storage,
+        &env,
+        &subscription_plan,
+        subscription_id,
+        &mut subscription,
+        period,
+    )?;
+
+    // Renew expired subscriptions from the prepaid balance
+    let (expiration, status) = (subscription.expiration, subscription.status.clone());
+    events.extend(process_renewal(
+        deps.branch(),
+        &env,
+        &subscription_plan,
+        subscription_id,
+        &mut subscription,
+    )?);
+
+    // Lapsed subscriptions also close the current usage period
+    let lapsed = subscription.status == SubscriptionStatus::Lapsed;
+    if lapsed {
+        if let Some(last_overage) = settle_overage(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &mut subscription,
+            u64::MAX,
+        )? {
+            overage = Some(match overage {
+                Some(overage) => Asset {
+                    amount: overage.amount + last_overage.amount,
+                    ..overage
+                },
+                None => last_overage,
+            });
+        }
+    }
+
+    // Pay the due installments from the prepaid balance and suspend overdue subscriptions
+    let installments = collect_installments(
+        deps.storage,
+        &subscription_plan,
+        subscription_id,
+        &mut subscription,
+        &env,
+    )?;
+    update_installment_status(&mut subscription, &env);
+
+    // Charges from the prepaid balance
+    for (charge, purpose) in [(&overage, "overage"), (&installments, "installment")] {
+        if let Some(charge) = charge.as_ref().filter(|charge| !charge.amount.is_zero()) {
+            events.push(events::balance_charged(
+                &env,
+                subscription_plan.organization_id,
+                subscription_id,
+                &subscription,
+                charge,
+                purpose,
+            ));
+        }
+    }
+
+    // Notify the hooks when the subscription was renewed or ran out
+    let hook_msg = if subscription.expiration > expiration {
+        Some(SubscriptionHookMsg::Renewed {
+            subscription_id,
+            plan_id: subscription.plan_id,
+            subscriber: subscription.subscriber.clone(),
+            expiration: subscription.expiration,
+        })
+    } else if subscription.status == SubscriptionStatus::Lapsed && status != subscription.status {
+        Some(SubscriptionHookMsg::Expired {
+            subscription_id,
+            plan_id: subscription.plan_id,
+            subscriber: subscription.subscriber.clone(),
+        })
+    } else {
+        None
+    };
+
+    let mut response = Response::new()
+        .add_attribute("action", "process_subscription")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("period", period.to_string())
+        .add_attribute("status", subscription.status.to_string());
+    if let Some(overage) = overage {
+        response = response.add_attribute("overage", overage.to_string());
+    }
+    if let Some(installments) = installments {
+        response = response.add_attribute("installments", installments.to_string());
+    }
+    if let Some(hook_msg) = hook_msg {
+        response = response.add_submessages(hook_messages(
+            deps.storage,
+            subscription_plan.organization_id,
+            hook_msg,
+        )?);
+    }
+
+    // Release the part of the held payments that was earned in the meantime
+    release_escrow(
+        deps.storage,
+        &env,
+        &subscription_plan,
+        subscription_id,
+        &subscription,
+    )?;
+
+    // Return the unused prepaid balance and the seat of lapsed subscriptions
+    if lapsed {
+        release_seat(deps.storage, subscription_id)?;
+        if let Some(balance) = withdraw_balance(&mut subscription) {
+            events.push(events::refund_issued(
+                &env,
+                subscription_plan.organization_id,
+                subscription_id,
+                &subscription,
+                &balance,
+                "balance",
+            ));
+            events.push(add_credit(
+                deps.storage,
+                &env,
+                &subscription.payer,
+                &balance,
+                "balance",
+                Some(subscription_id),
+            )?);
+            response = response.add_attribute("refund", balance.to_string());
+        }
+    }
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+
+    Ok(response.add_events(events))
+}
+
+// Renews an expired subscription from its prepaid balance following the dunning policy
+fn process_renewal(
+    deps: DepsMut,
+    env: &Env,
+    subscription_plan: &SubscriptionPlan,
+    subscription_id: u64,
+    subscription: &mut Subscription,
+) -> Result<Vec<Event>, ContractError> {
+    // Only expired or used up subscriptions are processed
+    let used_up = subscription.remaining_uses == Some(0);
+    if subscription.canceled
+        || subscription.status == SubscriptionStatus::Lapsed
+        || subscription.status == SubscriptionStatus::Paused
+        || (env.block.time < subscription.expiration && !used_up)
+    {
+        return Ok(vec![]);
+    }
+    let dunning = subscription_plan.dunning.clone().unwrap_or_default();
+    let organization_id = subscription_plan.organization_id;
+
+    // Lapse subscriptions of every plan kind that were used up or not renewed within the grace period
+    if used_up || env.block.time > grace_end(subscription) {
+        subscription.status = SubscriptionStatus::Lapsed;
+        return Ok(vec![events::subscription_lapsed(
+            env,
+            organization_id,
+            subscription_id,
+            subscription,
+        )]);
+    }
+
+    // Only recurring subscriptions are renewed
+    if !matches!(
+        subscription_plan.kind,
+        PlanKind::Recurring | PlanKind::Hybrid { .. }
+    ) {
+        return Ok(vec![]);
+    }
+
+    // The first attempt is made at the expiration and the retries follow the schedule
+    let attempt_offset = match subscription.renewal_attempts {
+        0 => Some(0),
+        attempts => dunning.retry_schedule.get(attempts as usize - 1).copied(),
+    };
+    let attempt_due = attempt_offset
+        .is_some_and(|offset| env.block.time >= subscription.expiration.plus_seconds(offset));
+    if !attempt_due {
+        return Ok(vec![]);
+    }
+
+    // Pay the next period from the prepaid balance when it covers the price, free periods are
+    // always renewed
+    if let Some((price, coupon)) =
+        renewal_price(deps.as_ref(), env, subscription_plan, subscription)
+    {
+        if let (Some(balance), Some(price)) = (subscription.balance.as_mut(), &price) {
+            balance.amount -= price.amount;
+        }
+        subscription.expiration = subscription
+            .expiration
+            .plus_seconds(plan_period_seconds(subscription_plan));
+        subscription.status = SubscriptionStatus::Active;
+        subscription.renewal_attempts = 0;
+        subscription.coupon = coupon;
+        subscription.price = price.clone();
+
+        let mut events = vec![];
+        if let Some(price) = price {
+            add_subscription_revenue(
+                deps.storage,
+                env,
+                subscription_plan,
+                subscription_id,
+                subscription,
+                &price,
+                false,
+            )?;
+            events.push(events::balance_charged(
+                env,
+                organization_id,
+                subscription_id,
+                subscription,
+                &price,
+                "renewal",
+            ));
+        }
+        events.push(events::subscription_renewed(
+            env,
+            organization_id,
+            subscription_id,
+            subscription,
+        ));
+        return Ok(events);
+    }
+
+    // The renewal failed, the subscription is past due until the grace period ends
+    let mut events = vec![];
+    if subscription.status != SubscriptionStatus::PastDue {
+        events.push(events::subscription_past_due(
+            env,
+            organization_id,
+            subscription_id,
+            subscription,
+            grace_end(subscription),
+        ));
+    }
+    subscription.status = SubscriptionStatus::PastDue;
+    subscription.renewal_attempts += 1;
+    let next_retry = dunning
+        .retry_schedule
+        .get(subscription.renewal_attempts as usize - 1)
+        .map(|offset| subscription.expiration.plus_seconds(*offset));
+    events.push(events::renewal_failed(
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+        next_retry,
+    ));
+
+    Ok(events)
+}
+
+// Price of the next period in the prepaid balance asset, None if the balance does not cover it.
+// Free periods need no balance and have no price
+fn renewal_price(
+    deps: Deps,
+    env: &Env,
+    subscription_plan: &SubscriptionPlan,
+    subscription: &Subscription,
+) -> Option<(Option<Asset>, Option<AppliedCoupon>)> {
+    let balance = subscription.balance.as_ref();
+    let plan_price = resolve_plan_price(
+        deps,
+        env,
+        subscription_plan,
+        balance.map(|balance| &balance.info),
+    )
+    .ok()?;
+    let mut coupon = subscription.coupon.clone();
+    let price = apply_coupon(&mut coupon, plan_price)
+        .ok()?
+        .filter(|price| !price.amount.is_zero());
+    let covered = match (&price, balance) {
+        (None, _) => true,
+        (Some(price), Some(balance)) => price.amount <= balance.amount,
+        (Some(_), None) => false,
+    };
+    covered.then_some((price, coupon))
+}
+
+// End of the grace period after the expiration of the subscription
+fn grace_end(subscription: &Subscription) -> Timestamp {
+    Timestamp::from_nanos(
+        subscription
+            .expiration
+            .nanos()
+            .saturating_add(subscription.grace_period.saturating_mul(1_000_000_000)),
+    )
+}
+
+// Empties the prepaid balance of the subscription and returns the withdrawn funds
+fn withdraw_balance(subscription: &mut Subscription) -> Option<Asset> {
+    let balance = subscription.balance.as_mut()?;
+    if balance.amount.is_zero() {
+        return None;
+    }
+    let withdrawn = balance.clone();
+    balance.amount = Uint128::zero();
+    Some(withdrawn)
+}
+
+// Charges the overage of the usage periods before end_period from the prepaid balance, the part
+// the balance cannot cover is recorded as unpaid on the period so processing never gets stuck
+fn settle_overage(
+    storage: &mut dyn Storage,
+    env: &Env,
+    subscription_plan: &SubscriptionPlan,
+    subscription_id: u64,
+    subscription: &mut Subscription,
+    end_period: u64,
+) -> StdResult<Option<Asset>> {
+    // Only hybrid plans charge for the usage above the included units
+    let (included_units, overage_prices) = match &subscription_plan.kind {
+        PlanKind::Hybrid {
+            included_units,
+            overage_prices,
+        } => (*included_units, overage_prices),
+        _ => return Ok(None),
+    };
+    let Some(mut balance) = subscription.balance.clone() else {
+        return Ok(None);
+    };
+    let Some(rate) = overage_prices.iter().find(|rate| rate.info == balance.info) else {
+        return Ok(None);
+    };
+
+    // Load the usage periods that were not closed yet
+    let periods = USAGE
+        .prefix(subscription_id)
+        .range(
+            storage,
+            None,
+            Some(Bound::exclusive(end_period)),
+            Order::Ascending,
+        )
+        .filter(|item| !matches!(item, Ok((_, usage)) if usage.settled))
+        .collect::<StdResult<Vec<(u64, PeriodUsage)>>>()?;
+
+    // Charge the units above the included units of each period
+    let mut overage = Asset {
+        info: balance.info.clone(),
+        amount: Uint128::zero(),
+    };
+    for (period, mut usage) in periods {
+        let overage_units = usage.units.saturating_sub(included_units);
+        let cost = rate.amount.saturating_mul(Uint128::from(overage_units));
+        usage.cost = cost.min(balance.amount);
+        usage.unpaid = cost - usage.cost;
+        usage.settled = true;
+        balance.amount -= usage.cost;
+        overage.amount += usage.cost;
+        USAGE.save(storage, (subscription_id, period), &usage)?;
+    }
+
+    // The charged overage becomes revenue of the organization
+    subscription.balance = Some(balance);
+    if !overage.amount.is_zero() {
+        add_subscription_revenue(
+            storage,
+            env,
+            subscription_plan,
+            subscription_id,
+            subscription,
+            &overage,
+            false,
+        )?;
+    }
+
+    Ok(Some(overage))
+}
+
+fn execute_pause_subscription(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Check that the sender is the subscriber
+    if info.sender != subscription.subscriber {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the subscription plan is pausable
+    if !subscription_plan.pausable {
+        return Err(ContractError::NotPausable {});
+    };
+
+    // Bundled subscriptions are managed through their bundle
+    if BUNDLED_SUBSCRIPTIONS.has(deps.storage, subscription_id) {
+        return Err(ContractError::BundledSubscription {});
+    };
+
+    // Check that the subscription is not already paused
+    if subscription.status == SubscriptionStatus::Paused {
+        return Err(ContractError::SubscriptionPaused {});
+    };
+
+    // Check that the subscription is active and in good standing
+    if subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+    if env.block.time > subscription.expiration {
+        return Err(ContractError::AlreadyExpired {});
+    };
+    if subscription.status != SubscriptionStatus::Active {
+        return Err(ContractError::NotPausable {});
+    };
+
+    // Freeze the remaining time of the subscription
+    subscription.status = SubscriptionStatus::Paused;
+    subscription.paused_at = Some(env.block.time);
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+
+    let remaining = remaining_seconds(&subscription, &env);
+    Ok(Response::new()
+        .add_event(events::subscription_paused(
+            &env,
+            subscription_plan.organization_id,
+            subscription_id,
+            &subscription,
+            remaining,
+        ))
+        .add_attribute("action", "pause_subscription")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("remaining", remaining.to_string()))
+}
+
+fn execute_resume_subscription(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Check that the sender is the subscriber
+    if info.sender != subscription.subscriber {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the subscription is paused
+    if subscription.status != SubscriptionStatus::Paused {
+        return Err(ContractError::NotPaused {});
+    };
+
+    // The pause ends now or when the maximum pause length was reached
+    let resumed_at = pause_end(&subscription_plan, &subscription)
+        .map_or(env.block.time, |pause_end| pause_end.min(env.block.time));
+    resume_subscription(&mut subscription, resumed_at);
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+
+    Ok(Response::new()
+        .add_event(events::subscription_resumed(
+            &env,
+            subscription_plan.organization_id,
+            subscription_id,
+            &subscription,
+        ))
+        .add_attribute("action", "resume_subscription")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("expiration", subscription.expiration.to_string()))
+}
+
+// When the pause of the subscription ends by itself, None if it is not paused or can stay paused
+fn pause_end(
+    subscription_plan: &SubscriptionPlan,
+    subscription: &Subscription,
+) -> Option<Timestamp> {
+    let paused_at = subscription.paused_at?;
+    let max_pause_duration = subscription_plan.max_pause_duration?;
+    Some(paused_at.plus_seconds(max_pause_duration))
+}
+
+// Restores the remaining time the subscription had when it was paused
+fn resume_subscription(subscription: &mut Subscription, resumed_at: Timestamp) {
+    if let Some(paused_at) = subscription.paused_at.take() {
+        let remaining = subscription.expiration.seconds() - paused_at.seconds();
+        subscription.expiration = resumed_at.plus_seconds(remaining);
+        subscription.status = SubscriptionStatus::Active;
+    }
+}
+
+// Seconds left on the subscription, frozen while it is paused
+fn remaining_seconds(subscription: &Subscription, env: &Env) -> u64 {
+    let now = subscription.paused_at.unwrap_or(env.block.time);
+    subscription
+        .expiration
+        .seconds()
+        .saturating_sub(now.seconds())
+}
+
+fn execute_set_usage_reporters(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    reporters: Vec<String>,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Validate and save the reporters
+    let reporters = reporters
+        .iter()
+        .map(|reporter| deps.api.addr_validate(reporter))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    USAGE_REPORTERS.save(deps.storage, organization_id, &reporters)?;
+
+    Ok(Response::new()
+        .add_event(events::usage_reporters_updated(
+            &env,
+            organization_id,
+            &reporters,
+        ))
+        .add_attribute("action", "set_usage_reporters")
+        .add_attribute("organization_id", organization_id.to_string()))
+}
+
+fn execute_add_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    contract: String,
+    on_failure: HookFailurePolicy,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the hook contract is not registered yet
+    let contract = deps.api.addr_validate(&contract)?;
+    let mut hooks = HOOKS
+        .may_load(deps.storage, organization_id)?
+        .unwrap_or_default();
+    if hooks.iter().any(|hook| hook.contract == contract) {
+        return Err(ContractError::HookAlreadyExists {});
+    }
+
+    // Register the hook contract
+    hooks.push(Hook {
+        contract: contract.clone(),
+        on_failure,
+    });
+    HOOKS.save(deps.storage, organization_id, &hooks)?;
+
+    Ok(Response::new()
+        .add_event(events::hooks_updated(
+            &env,
+            organization_id,
+            &contract,
+            "added",
+        ))
+        .add_attribute("action", "add_hook")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("contract", contract))
+}
+
+fn execute_remove_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    contract: String,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Remove the hook contract
+    let contract = deps.api.addr_validate(&contract)?;
+    let mut hooks = HOOKS
+        .may_load(deps.storage, organization_id)?
+        .unwrap_or_default();
+    let index = hooks
+        .iter()
+        .position(|hook| hook.contract == contract)
+        .ok_or(ContractError::HookNotFound {})?;
+    hooks.remove(index);
+    HOOKS.save(deps.storage, organization_id, &hooks)?;
+
+    Ok(Response::new()
+        .add_event(events::hooks_updated(
+            &env,
+            organization_id,
+            &contract,
+            "removed",
+        ))
+        .add_attribute("action", "remove_hook")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("contract", contract))
+}
+
+// Messages notifying the organization's hook contracts, each failing as its policy says
+fn hook_messages(
+    storage: &dyn Storage,
+    organization_id: u32,
+    msg: SubscriptionHookMsg,
+) -> StdResult<Vec<SubMsg>> {
+    HOOKS
+        .may_load(storage, organization_id)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|hook| {
+            let hook_msg = msg.clone().into_cosmos_msg(hook.contract.to_string())?;
+            Ok(match hook.on_failure {
+                HookFailurePolicy::Revert => SubMsg::new(hook_msg),
+                HookFailurePolicy::Ignore => SubMsg::reply_on_error(hook_msg, HOOK_REPLY_ID),
+            })
+        })
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match (msg.id, msg.result) {
+        // The failure of an ignored hook is logged and the transaction goes through
+        (HOOK_REPLY_ID, SubMsgResult::Err(error)) => {
+            Ok(Response::new().add_event(events::hook_failed(&env, &error)))
+        }
+        (id, _) => Err(StdError::generic_err(format!("Unknown reply id {}", id)).into()),
+    }
+}
+
+// Index of the usage period the subscription is currently in
+fn usage_period(
+    subscription_plan: &SubscriptionPlan,
+    subscription: &Subscription,
+    env: &Env,
+) -> u64 {
+    let elapsed = env.block.time.seconds() - subscription.started.seconds();
+    elapsed
+        .checked_div(plan_period_seconds(subscription_plan))
+        .unwrap_or_default()
+}
+
+// Resolves the price of a single period of the plan in the asset the payer pays with
+fn resolve_plan_price(
+    deps: Deps,
+    env: &Env,
+    subscription_plan: &SubscriptionPlan,
+    payment_asset: Option<&AssetInfo>,
+) -> Result<Option<Asset>, ContractError> {
+    // Plans denominated in a reference currency are converted with the oracle
+    if let Some(reference_price) = &subscription_plan.reference_price {
+        let asset = payment_asset
+            .filter(|asset| reference_price.assets.contains(asset))
+            .ok_or(ContractError::InvalidPayment {})?;
+        return Ok(Some(convert_reference_price(
+            deps,
+            env,
+            reference_price,
+            asset,
+        )?));
+    }
+
+    match payment_asset {
+        Some(asset) => Ok(Some(
+            subscription_plan
+                .prices
+                .iter()
+                .find(|price| price.info == *asset)
+                .cloned()
+                .ok_or(ContractError::InvalidPayment {})?,
+        )),
+        None => Ok(subscription_plan.prices.first().cloned()),
+    }
+}
+
+// Converts the reference price into the given asset using the oracle
+fn convert_reference_price(
+    deps: Deps,
+    env: &Env,
+    reference_price: &ReferencePrice,
+    asset: &AssetInfo,
+) -> Result<Asset, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let oracle = config.oracle.ok_or(ContractError::OracleNotConfigured {})?;
+
+    // Query the price of a single unit of the asset in the reference currency
+    let oracle_price: OraclePriceResponse = deps.querier.query_wasm_smart(
+        oracle,
+        &OracleQueryMsg::Price {
+            asset: asset.clone(),
+            currency: reference_price.currency.clone(),
+        },
+    )?;
+
+    // Check that the oracle price is recent and valid
+    if oracle_price
+        .updated_at
+        .plus_seconds(config.oracle_max_staleness)
+        < env.block.time
+    {
+        return Err(ContractError::StalePrice {});
+    }
+    if oracle_price.price.is_zero() {
+        return Err(ContractError::InvalidOraclePrice {});
+    }
+
+    // Round up so the organization never receives less than the reference price
+    let numerator = reference_price.amount.atomics();
+    let denominator = oracle_price.price.atomics();
+    let amount = numerator
+        .checked_add(denominator - Uint128::one())
+        .map_err(StdError::overflow)?
+        / denominator;
+
+    Ok(Asset {
+        info: asset.clone(),
+        amount,
+    })
+}
+
+// Applies the coupon discount to the price and consumes one discounted period
+fn apply_coupon(
+    applied_coupon: &mut Option<AppliedCoupon>,
+    price: Option<Asset>,
+) -> Result<Option<Asset>, ContractError> {
+    let (applied_coupon, price) = match (applied_coupon.as_mut(), price) {
+        (Some(applied_coupon), Some(price)) => (applied_coupon, price),
+        (_, price) => return Ok(price),
+    };
+
+    match applied_coupon.remaining_periods {
+        Some(0) => Ok(Some(price)),
+        Some(remaining_periods) => {
+            applied_coupon.remaining_periods = Some(remaining_periods - 1);
+            Ok(Some(applied_coupon.discount.apply(&price)?))
+        }
+        None => Ok(Some(applied_coupon.discount.apply(&price)?)),
+    }
+}
+
+// Checks that the payment covers the price and returns any excess to send back
+fn settle_payment(
+    subscription_plan: &SubscriptionPlan,
+    payment: Option<Asset>,
+    price: Option<&Asset>,
+    max_price: Option<Uint128>,
+) -> Result<Option<Asset>, ContractError> {
+    let price_amount = price.map(|price| price.amount).unwrap_or_default();
+
+    // Check that the price is within the bound set by the payer
+    if let Some(max_price) = max_price {
+        if price_amount > max_price {
+            return Err(ContractError::SlippageExceeded {});
+        }
+    }
+
+    let payment = match payment {
+        Some(payment) => payment,
+        None if price_amount.is_zero() => return Ok(None),
+        None => return Err(ContractError::InvalidPayment {}),
+    };
+
+    // Fixed prices must be paid exactly, oracle prices can be overpaid
+    if payment.amount < price_amount
+        || (subscription_plan.reference_price.is_none() && payment.amount != price_amount)
+    {
+        return Err(ContractError::InvalidPayment {});
+    }
+
+    let excess = payment.amount - price_amount;
+    if excess.is_zero() {
+        return Ok(None);
+    }
+    Ok(Some(Asset {
+        info: payment.info,
+        amount: excess,
+    }))
+}
+
+// Validates the coupon for the subscriber and records the redemption
+fn redeem_coupon(
+    storage: &mut dyn Storage,
+    env: &Env,
+    organization_id: u32,
+    plan_id: u64,
+    subscriber: &Addr,
+    code: &str,
+) -> Result<AppliedCoupon, ContractError> {
+    // Load the coupon
+    let code_hash = hash_coupon_code(&env.contract.address, organization_id, code);
+    let mut coupon = COUPONS
+        .may_load(storage, (organization_id, code_hash.clone()))?
+        .ok_or(ContractError::InvalidCoupon {})?;
+
+    // Check that the coupon is not expired
+    if let Some(expiration) = coupon.expiration {
+        if env.block.time >= expiration {
+            return Err(ContractError::CouponExpired {});
+        }
+    }
+
+    // Check that the coupon applies to the subscription plan
+    if let Some(plan_ids) = &coupon.plan_ids {
+        if !plan_ids.contains(&plan_id) {
+            return Err(ContractError::CouponNotApplicable {});
+        }
+    }
+
+    // Check that the coupon has redemptions left
+    if let Some(max_redemptions) = coupon.max_redemptions {
+        if coupon.redemptions >= max_redemptions {
+            return Err(ContractError::CouponExhausted {});
+        }
+    }
+
+    // Check that the subscriber has not reached the per-user limit
+    let user_redemptions = COUPON_REDEMPTIONS
+        .may_load(
+            storage,
+            (organization_id, code_hash.clone(), subscriber.clone()),
+        )?
+        .unwrap_or_default();
+    if let Some(per_user_limit) = coupon.per_user_limit {
+        if user_redemptions >= per_user_limit {
+            return Err(ContractError::CouponLimitReached {});
+        }
+    }
+
+    // Record the redemption
+    coupon.redemptions += 1;
+    COUPONS.save(storage, (organization_id, code_hash.clone()), &coupon)?;
+    COUPON_REDEMPTIONS.save(
+        storage,
+        (organization_id, code_hash.clone(), subscriber.clone()),
+        &(user_redemptions + 1),
+    )?;
+
+    Ok(AppliedCoupon {
+        code_hash,
+        discount: coupon.discount,
+        remaining_periods: match coupon.duration {
+            CouponDuration::Periods(periods) => Some(periods),
+            CouponDuration::Forever => None,
+        },
+    })
+}
+
+// Coupon codes are only stored as their hex encoded sha256 hash, salted with the hub address
+// and the organization id so the same code hashes differently in every organization and no
+// precomputed table of common codes applies. The salt is public though, so a short or guessable
+// code can still be brute forced from state and organizations should use long random codes.
+// Coupons may cover several plans, so the plan id can not be part of the salt.
+fn hash_coupon_code(hub: &Addr, organization_id: u32, code: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(hub.as_bytes());
+    hasher.update(organization_id.to_be_bytes());
+    hasher.update(code.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_create_coupon(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    code: String,
+    discount: Discount,
+    duration: CouponDuration,
+    max_redemptions: Option<u32>,
+    per_user_limit: Option<u32>,
+    expiration: Option<Timestamp>,
+    plan_ids: Option<Vec<u64>>,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the code and the discount are valid
+    let is_valid_discount = match &discount {
+        Discount::Percent(percent) => *percent > 0 && *percent <= 100,
+        Discount::Fixed(discount) => {
+            discount.info.validate(deps.api)?;
+            !discount.amount.is_zero()
+        }
+    };
+    if code.is_empty() || !is_valid_discount || duration == CouponDuration::Periods(0) {
+        return Err(ContractError::InvalidCoupon {});
+    };
+
+    // Check that the restricted plans belong to the organization
+    if let Some(plan_ids) = &plan_ids {
+        for plan_id in plan_ids {
+            let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, *plan_id)?;
+            if subscription_plan.organization_id != organization_id {
+                return Err(ContractError::Unauthorized {});
+            }
+        }
+    };
+
+    // Check that the coupon does not exist yet
+    let code_hash = hash_coupon_code(&env.contract.address, organization_id, &code);
+    if COUPONS.has(deps.storage, (organization_id, code_hash.clone())) {
+        return Err(ContractError::CouponAlreadyExists {});
+    };
+
+    // Create the coupon
+    let coupon = Coupon {
+        discount,
+        duration,
+        max_redemptions,
+        per_user_limit,
+        expiration,
+        plan_ids,
+        redemptions: 0,
+    };
+    COUPONS.save(deps.storage, (organization_id, code_hash.clone()), &coupon)?;
+
+    Ok(Response::new()
+        .add_event(events::coupon_created(&env, organization_id, &code_hash))
+        .add_attribute("action", "create_coupon")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("code_hash", code_hash))
+}
+
+fn execute_join_waitlist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+
+    // Check that the plan has a waitlist
+    if !subscription_plan.waitlist {
+        return Err(ContractError::WaitlistDisabled {});
+    }
+
+    // Check that the user is not queued or admitted already
+    let key = (plan_id, info.sender.clone());
+    if WAITLIST_POSITIONS.has(deps.storage, key.clone())
+        || WAITLIST_ADMISSIONS.has(deps.storage, key.clone())
+    {
+        return Err(ContractError::AlreadyWaitlisted {});
+    }
+
+    // Queue the user at the end of the waitlist
+    let position = WAITLIST_NEXT_POSITION
+        .may_load(deps.storage, plan_id)?
+        .unwrap_or_default();
+    WAITLIST_NEXT_POSITION.save(deps.storage, plan_id, &(position + 1))?;
+    WAITLIST.save(deps.storage, (plan_id, position), &info.sender)?;
+    WAITLIST_POSITIONS.save(deps.storage, key, &position)?;
+
+    Ok(Response::new()
+        .add_event(events::waitlist_updated(
+            &env,
+            subscription_plan.organization_id,
+            plan_id,
+            &info.sender,
+            "joined",
+        ))
+        .add_attribute("action", "join_waitlist")
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_attribute("position", position.to_string()))
+}
+
+fn execute_leave_waitlist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+
+    // Remove the user from the waitlist
+    let key = (plan_id, info.sender.clone());
+    let position = WAITLIST_POSITIONS
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::NotWaitlisted {})?;
+    WAITLIST_POSITIONS.remove(deps.storage, key);
+    WAITLIST.remove(deps.storage, (plan_id, position));
+
+    Ok(Response::new()
+        .add_event(events::waitlist_updated(
+            &env,
+            subscription_plan.organization_id,
+            plan_id,
+            &info.sender,
+            "left",
+        ))
+        .add_attribute("action", "leave_waitlist")
+        .add_attribute("subscription_plan_id", plan_id.to_string()))
+}
+
+fn execute_admit_from_waitlist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+    count: u32,
+) -> Result<Response, ContractError> {
+    // Load the subscription plan and the organization
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+    let organization = ORGANIZATIONS.load(deps.storage, subscription_plan.organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Admit no more users than there are free seats, each admission reserves one
+    let count = match subscription_plan.max_subscribers {
+        Some(max_subscribers) => count.min(free_seats(deps.storage, plan_id, max_subscribers)?),
+        None => count,
+    };
+
+    // Take the next users in the order they joined
+    let admitted = WAITLIST
+        .prefix(plan_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(count as usize)
+        .collect::<StdResult<Vec<(u64, Addr)>>>()?;
+
+    // Move them from the waitlist to the admissions
+    let mut response = Response::new()
+        .add_attribute("action", "admit_from_waitlist")
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_attribute("admitted", admitted.len().to_string());
+    for (position, user) in admitted {
+        WAITLIST.remove(deps.storage, (plan_id, position));
+        WAITLIST_POSITIONS.remove(deps.storage, (plan_id, user.clone()));
+        WAITLIST_ADMISSIONS.save(deps.storage, (plan_id, user.clone()), &true)?;
+        response = response.add_event(events::waitlist_updated(
+            &env,
+            subscription_plan.organization_id,
+            plan_id,
+            &user,
+            "admitted",
+        ));
+    }
+
+    Ok(response)
+}
+
+fn execute_set_plan_access(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+    access: PlanAccess,
+) -> Result<Response, ContractError> {
+    // Load the subscription plan and the organization
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+    let organization = ORGANIZATIONS.load(deps.storage, subscription_plan.organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Validate the access control
+    match &access {
+        PlanAccess::MerkleAllowlist { root } => {
+            let mut root_hash = [0u8; 32];
+            hex::decode_to_slice(root, &mut root_hash)
+                .map_err(|_| ContractError::InvalidMerkleRoot {})?;
+        }
+        PlanAccess::Prerequisite {
+            plan_id: prerequisite_plan_id,
+        } => {
+            if *prerequisite_plan_id == plan_id {
+                return Err(ContractError::InvalidPrerequisite {});
+            }
+            SUBSCRIPTION_PLANS.load(deps.storage, *prerequisite_plan_id)?;
+        }
+        PlanAccess::Public | PlanAccess::Allowlist => {}
+    }
+
+    // Public plans do not need an entry
+    match access {
+        PlanAccess::Public => PLAN_ACCESS.remove(deps.storage, plan_id),
+        access => PLAN_ACCESS.save(deps.storage, plan_id, &access)?,
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_plan_access")
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_event(events::plan_access_updated(
+            &env,
+            subscription_plan.organization_id,
+            plan_id,
+        )))
+}
+
+fn execute_update_plan_allowlist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    // Load the subscription plan and the organization
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+    let organization = ORGANIZATIONS.load(deps.storage, subscription_plan.organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Update the allowlist
+    for address in add {
+        let address = deps.api.addr_validate(&address)?;
+        PLAN_ALLOWLIST.save(deps.storage, (plan_id, address), &true)?;
+    }
+    for address in remove {
+        let address = deps.api.addr_validate(&address)?;
+        PLAN_ALLOWLIST.remove(deps.storage, (plan_id, address));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_plan_allowlist")
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_event(events::plan_access_updated(
+            &env,
+            subscription_plan.organization_id,
+            plan_id,
+        )))
+}
+
+fn execute_set_affiliate_reward(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+    reward: Option<AffiliateReward>,
+) -> Result<Response, ContractError> {
+    // Load the subscription plan and the organization
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+    let organization = ORGANIZATIONS.load(deps.storage, subscription_plan.organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    match reward {
+        Some(reward) => {
+            // Check that the commission is a part of the payment
+            if reward.bps == 0 || reward.bps > 10_000 {
+                return Err(ContractError::InvalidAffiliateReward {});
+            }
+            AFFILIATE_REWARDS.save(deps.storage, plan_id, &reward)?;
+        }
+        None => AFFILIATE_REWARDS.remove(deps.storage, plan_id),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_affiliate_reward")
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_event(events::affiliate_reward_updated(
+            &env,
+            subscription_plan.organization_id,
+            plan_id,
+        )))
+}
+
+fn execute_claim_referral_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    // Load the claimable rewards of the sender
+    let rewards = REFERRAL_REWARDS
+        .prefix(info.sender.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Asset)>>>()?;
+    if rewards.iter().all(|(_, reward)| reward.amount.is_zero()) {
+        return Err(ContractError::NoReferralRewards {});
+    }
+
+    // Pay out every reward
+    let mut response = Response::new()
+        .add_attribute("action", "claim_referral_rewards")
+        .add_attribute("referrer", &info.sender);
+    for (key, reward) in rewards {
+        REFERRAL_REWARDS.remove(deps.storage, (info.sender.clone(), key));
+        if reward.amount.is_zero() {
+            continue;
+        }
+        response = response
+            .add_message(reward.transfer_msg(&info.sender)?)
+            .add_event(events::referral_rewards_claimed(
+                &env,
+                &info.sender,
+                &reward,
+            ))
+            .add_attribute("reward", reward.to_string());
+    }
+
+    Ok(response)
+}
+
+fn execute_set_token_gate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+    gate: Option<TokenGate>,
+) -> Result<Response, ContractError> {
+    // Load the subscription plan and the organization
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+    let organization = ORGANIZATIONS.load(deps.storage, subscription_plan.organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    match gate {
+        Some(gate) => {
+            // Validate the token contract, the threshold and the holder discount
+            let is_valid_requirement = match &gate.requirement {
+                TokenRequirement::Cw20 {
+                    address,
+                    min_balance,
+                } => {
+                    deps.api.addr_validate(address.as_str())?;
+                    !min_balance.is_zero()
+                }
+                TokenRequirement::Cw721 { address } => {
+                    deps.api.addr_validate(address.as_str())?;
+                    true
+                }
+            };
+            let is_valid_discount = gate
+                .holder_discount
+                .is_none_or(|percent| percent > 0 && percent <= 100);
+            if !is_valid_requirement || !is_valid_discount {
+                return Err(ContractError::InvalidTokenGate {});
+            }
+
+            TOKEN_GATES.save(deps.storage, plan_id, &gate)?;
+        }
+        None => TOKEN_GATES.remove(deps.storage, plan_id),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_token_gate")
+        .add_attribute("subscription_plan_id", plan_id.to_string())
+        .add_event(events::token_gate_updated(
+            &env,
+            subscription_plan.organization_id,
+            plan_id,
+        )))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_create_bundle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    name: String,
+    components: Vec<BundleComponent>,
+    prices: Vec<Asset>,
+    duration: u8,
+    duration_unit: DurationUnit,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check the bundle details against the configured limits
+    let limits = CONFIG.load(deps.storage)?.limits;
+    validation::validate_name(&limits, &name)?;
+    validation::validate_prices(&limits, &prices)?;
+    if duration == 0 {
+        return Err(ContractError::InvalidDuration {});
+    }
+
+    // Check that the bundle has a price and every price accepts a different asset
+    if prices.is_empty() {
+        return Err(ContractError::InvalidPrices {});
+    }
+    for (index, price) in prices.iter().enumerate() {
+        price.info.validate(deps.api)?;
+        if prices[..index].iter().any(|other| other.info == price.info) {
+            return Err(ContractError::InvalidPrices {});
+        }
+    }
+
+    // Check that the bundle has several different plans and the shares add up to the whole price
+    let total_share: u32 = components
+        .iter()
+        .map(|component| component.share as u32)
+        .sum();
+    if components.len() < 2
+        || total_share != 100
+        || components.iter().enumerate().any(|(index, component)| {
+            component.share == 0
+                || components[..index]
+                    .iter()
+                    .any(|other| other.plan_id == component.plan_id)
+        })
+    {
+        return Err(ContractError::InvalidBundle {});
+    }
+
+    // Only recurring plans can be bundled, other organizations have to approve the bundle
+    let mut pending_approvals = vec![];
+    for component in &components {
+        let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, component.plan_id)?;
+        if subscription_plan.kind != PlanKind::Recurring {
+            return Err(ContractError::InvalidBundle {});
+        }
+        if subscription_plan.organization_id != organization_id
+            && !pending_approvals.contains(&subscription_plan.organization_id)
+        {
+            pending_approvals.push(subscription_plan.organization_id);
+        }
+    }
+
+    // Load and save the bundle id counter
+    let bundle_id = BUNDLE_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    BUNDLE_ID.save(deps.storage, &bundle_id)?;
+
+    // Save the bundle
+    let bundle = Bundle {
+        organization_id,
+        name,
+        components,
+        prices,
+        duration,
+        duration_unit,
+        pending_approvals,
+    };
+    BUNDLES.save(deps.storage, bundle_id, &bundle)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_bundle")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("bundle_id", bundle_id.to_string())
+        .add_event(events::bundle_created(&env, organization_id, bundle_id)))
+}
+
+fn execute_approve_bundle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bundle_id: u64,
+    organization_id: u32,
+) -> Result<Response, ContractError> {
+    // Load the bundle and the organization
+    let mut bundle = BUNDLES.load(deps.storage, bundle_id)?;
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the owner of an organization that still has to approve the bundle
+    if info.sender != organization.owner || !bundle.pending_approvals.contains(&organization_id) {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Record the approval
+    bundle.pending_approvals.retain(|id| *id != organization_id);
+    BUNDLES.save(deps.storage, bundle_id, &bundle)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_bundle")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("bundle_id", bundle_id.to_string())
+        .add_event(events::bundle_approved(&env, organization_id, bundle_id)))
+}
+
+fn execute_subscribe_bundle(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
+    bundle_id: u64,
+    beneficiary: Option<String>,
+) -> Result<Response, ContractError> {
+    // Load the bundle
+    let bundle = BUNDLES.load(deps.storage, bundle_id)?;
+
+    // Check that every organization of the bundle approved it
+    if !bundle.pending_approvals.is_empty() {
+        return Err(ContractError::BundleNotApproved {});
+    };
+
+    // The beneficiary is entitled to the subscriptions, the payer pays for them
+    let subscriber = maybe_addr(deps.api, beneficiary)?.unwrap_or_else(|| payer.clone());
+
+    // Check that the payment matches the bundle price and split it between the components
+    let price = bundle_price(&bundle, payment)?;
+    let shares = split_bundle_price(&bundle, &price);
+    let expiration = env
+        .block
+        .time
+        .plus_seconds(period_seconds(bundle.duration, &bundle.duration_unit));
+
+    // Load and save the bundle subscription id counter
+    let bundle_subscription_id = BUNDLE_SUBSCRIPTION_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    BUNDLE_SUBSCRIPTION_ID.save(deps.storage, &bundle_subscription_id)?;
+
+    // Create a linked subscription for every component
+    let mut response = Response::new();
+    let mut subscription_ids = vec![];
+    let mut payments = vec![];
+    for (component, share) in bundle.components.iter().zip(shares) {
+        let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, component.plan_id)?;
+
+        // Check that the subscriber is allowed to subscribe to the component, bundles take no
+        // proofs so components behind a Merkle allowlist cannot be subscribed through them
+        check_access(deps.as_ref(), &env, component.plan_id, &subscriber, None)?;
+
+        // Check that the subscriber holds the tokens required by the component, holder
+        // discounts do not apply since the bundle has its own price
+        check_token_gate(deps.as_ref(), component.plan_id, &subscriber)?;
+
+        // Check if the subscriber already has a subscription to the component
+        if let Some(existing_subscription_id) =
+            USER_SUBSCRIPTIONS.may_load(deps.storage, (subscriber.clone(), component.plan_id))?
+        {
+            let existing_subscription =
+                SUBSCRIPTIONS.load(deps.storage, existing_subscription_id)?;
+            if is_held(&existing_subscription, &env) {
+                return Err(ContractError::AlreadySubscribed {});
+            }
+
+            // The replaced subscription gives up its seat
+            release_seat(deps.storage, existing_subscription_id)?;
+        }
+
+        // Check that the plan has room for the subscriber
+        check_capacity(
+            deps.storage,
+            &subscription_plan,
+            component.plan_id,
+            &subscriber,
+        )?;
+
+        // Create the subscription and link it to the bundle
+        let organization_id = subscription_plan.organization_id;
+        let subscription = Subscription {
+            subscriber: subscriber.clone(),
+            payer: payer.clone(),
+            plan_id: component.plan_id,
+            started: env.block.time,
+            expiration,
+            canceled: false,
+            status: SubscriptionStatus::Active,
+            grace_period: 0,
+            renewal_attempts: 0,
+            paused_at: None,
+            price: Some(share.clone()),
+            coupon: None,
+            balance: None,
+            remaining_uses: None,
+            installments: None,
+            referrer: None,
+        };
+        let subscription_id = save_new_subscription(deps.storage, &subscription)?;
+        BUNDLED_SUBSCRIPTIONS.save(deps.storage, subscription_id, &bundle_subscription_id)?;
+        subscription_ids.push(subscription_id);
+
+        response = response
+            .add_event(events::subscription_created(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+            ))
+            .add_submessages(hook_messages(
+                deps.storage,
+                organization_id,
+                SubscriptionHookMsg::Created {
+                    subscription_id,
+                    plan_id: component.plan_id,
+                    subscriber: subscriber.clone(),
+                    expiration,
+                },
+            )?);
+        if !share.amount.is_zero() {
+            response = response.add_event(events::payment_received(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+                &payer,
+                &share,
+                "bundle",
+            ));
+        }
+        payments.push((subscription_plan, subscription_id, subscription, share));
+    }
+
+    // Save the bundle subscription
+    let bundle_subscription = BundleSubscription {
+        bundle_id,
+        subscriber: subscriber.clone(),
+        payer: payer.clone(),
+        subscription_ids,
+        expiration,
+        canceled: false,
+    };
+    BUNDLE_SUBSCRIPTIONS.save(deps.storage, bundle_subscription_id, &bundle_subscription)?;
+
+    // Collect the shares of the organizations, refundable components hold theirs in escrow
+    for (subscription_plan, subscription_id, subscription, share) in payments {
+        add_subscription_revenue(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &subscription,
+            &share,
+            true,
+        )?;
+    }
+
+    Ok(response
+        .add_attribute("action", "subscribe_bundle")
+        .add_attribute("bundle_id", bundle_id.to_string())
+        .add_attribute("bundle_subscription_id", bundle_subscription_id.to_string())
+        .add_attribute("subscriber", subscriber)
+        .add_attribute("payer", payer)
+        .add_attribute("price", price.to_string()))
+}
+
+fn execute_renew_bundle(
+    deps: DepsMut,
+    env: Env,
+    payer: Addr,
+    payment: Option<Asset>,
+    bundle_subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the bundle subscription and the bundle
+    let mut bundle_subscription =
+        BUNDLE_SUBSCRIPTIONS.load(deps.storage, bundle_subscription_id)?;
+    let bundle = BUNDLES.load(deps.storage, bundle_subscription.bundle_id)?;
+
+    // Check that the bundle subscription is not canceled
+    if bundle_subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+
+    // Expired bundles start anew, only one period can be paid in advance
+    let period = period_seconds(bundle.duration, &bundle.duration_unit);
+    let start = env.block.time.max(bundle_subscription.expiration);
+    if start.seconds() - env.block.time.seconds() > period {
+        return Err(ContractError::NotRenewable {});
+    };
+    let expiration = start.plus_seconds(period);
+
+    // Check that the payment matches the bundle price and split it between the components
+    let price = bundle_price(&bundle, payment)?;
+    let shares = split_bundle_price(&bundle, &price);
+
+    // Extend every linked subscription
+    let mut response = Response::new();
+    for (subscription_id, share) in bundle_subscription.subscription_ids.iter().zip(shares) {
+        let subscription_id = *subscription_id;
+        let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+        let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+        // Check that the subscription was not replaced by a newer one
+        let current_subscription_id = USER_SUBSCRIPTIONS.may_load(
+            deps.storage,
+            (subscription.subscriber.clone(), subscription.plan_id),
+        )?;
+        if current_subscription_id != Some(subscription_id) {
+            return Err(ContractError::NotRenewable {});
+        };
+
+        // Lapsed subscriptions gave up their seat and need a free one
+        if !SEATS.has(deps.storage, subscription_id) {
+            check_capacity(
+                deps.storage,
+                &subscription_plan,
+                subscription.plan_id,
+                &subscription.subscriber,
+            )?;
+            occupy_seat(deps.storage, subscription.plan_id, subscription_id)?;
+        }
+
+        // Extend the subscription by one period and collect the share of the organization,
+        // whoever renews does not become the payer
+        let organization_id = subscription_plan.organization_id;
+        subscription.expiration = expiration;
+        subscription.status = SubscriptionStatus::Active;
+        subscription.renewal_attempts = 0;
+        subscription.price = Some(share.clone());
+        SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+        add_subscription_revenue(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &subscription,
+            &share,
+            false,
+        )?;
+
+        // Restore the subscription in the plan's list if it expired
+        SUBSCRIPTION_PLAN_SUBSCRIPTIONS.save(
+            deps.storage,
+            (subscription.plan_id, subscription.subscriber.clone()),
+            &subscription_id,
+        )?;
+
+        response = response
+            .add_event(events::subscription_renewed(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+            ))
+            .add_submessages(hook_messages(
+                deps.storage,
+                organization_id,
+                SubscriptionHookMsg::Renewed {
+                    subscription_id,
+                    plan_id: subscription.plan_id,
+                    subscriber: subscription.subscriber.clone(),
+                    expiration,
+                },
+            )?);
+        if !share.amount.is_zero() {
+            response = response.add_event(events::payment_received(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+                &payer,
+                &share,
+                "bundle_renewal",
+            ));
+        }
+    }
+
+    // Save the bundle subscription
+    bundle_subscription.expiration = expiration;
+    BUNDLE_SUBSCRIPTIONS.save(deps.storage, bundle_subscription_id, &bundle_subscription)?;
+
+    Ok(response
+        .add_attribute("action", "renew_bundle")
+        .add_attribute("bundle_subscription_id", bundle_subscription_id.to_string())
+        .add_attribute("payer", payer)
+        .add_attribute("price", price.to_string())
+        .add_attribute("expiration", expiration.to_string()))
+}
+
+fn execute_cancel_bundle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bundle_subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the bundle subscription
+    let mut bundle_subscription =
+        BUNDLE_SUBSCRIPTIONS.load(deps.storage, bundle_subscription_id)?;
+
+    // Check that the sender is the subscriber or the payer of the bundle
+    if info.sender != bundle_subscription.subscriber && info.sender != bundle_subscription.payer {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the bundle subscription is not already canceled
+    if bundle_subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+
+    // Check that the bundle subscription is not expired
+    if env.block.time > bundle_subscription.expiration {
+        return Err(ContractError::AlreadyExpired {});
+    };
+
+    // Check that every component is cancelable
+    let mut components = vec![];
+    for subscription_id in &bundle_subscription.subscription_ids {
+        let subscription = SUBSCRIPTIONS.load(deps.storage, *subscription_id)?;
+        let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+        if !subscription_plan.cancelable {
+            return Err(ContractError::NotCancelable {});
+        };
+        components.push((*subscription_id, subscription, subscription_plan));
+    }
+
+    // Cancel the bundle subscription
+    bundle_subscription.canceled = true;
+    BUNDLE_SUBSCRIPTIONS.save(deps.storage, bundle_subscription_id, &bundle_subscription)?;
+
+    // Cancel every linked subscription and free its seat
+    let mut response = Response::new();
+    for (subscription_id, mut subscription, subscription_plan) in components {
+        // Take the unused part of the component's share out of the escrow as the refund
+        let refund = refund_escrow(
+            deps.storage,
+            &env,
+            &subscription_plan,
+            subscription_id,
+            &subscription,
+        )?;
+
+        subscription.canceled = true;
+        SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+        release_seat(deps.storage, subscription_id)?;
+
+        // Remove the subscription from the subscription plan's list of subscriptions
+        SUBSCRIPTION_PLAN_SUBSCRIPTIONS.remove(
+            deps.storage,
+            (subscription.plan_id, subscription.subscriber.clone()),
+        );
+
+        let organization_id = subscription_plan.organization_id;
+        response = response
+            .add_event(events::subscription_canceled(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+            ))
+            .add_submessages(hook_messages(
+                deps.storage,
+                organization_id,
+                SubscriptionHookMsg::Canceled {
+                    subscription_id,
+                    plan_id: subscription.plan_id,
+                    subscriber: subscription.subscriber.clone(),
+                },
+            )?);
+
+        // Credit the unused part of refundable components to the payer
+        if let Some(refund) = refund.filter(|refund| !refund.amount.is_zero()) {
+            let credit_event = add_credit(
+                deps.storage,
+                &env,
+                &subscription.payer,
+                &refund,
+                "refund",
+                Some(subscription_id),
+            )?;
+            response = response
+                .add_event(credit_event)
+                .add_event(events::refund_issued(
+                    &env,
+                    organization_id,
+                    subscription_id,
+                    &subscription,
+                    &refund,
+                    "unused_time",
+                ))
+                .add_attribute("refund", refund.to_string());
+        }
+    }
+
+    Ok(response
+        .add_attribute("action", "cancel_bundle")
+        .add_attribute("bundle_id", bundle_subscription.bundle_id.to_string())
+        .add_attribute("bundle_subscription_id", bundle_subscription_id.to_string()))
+}
+
+// Checks that the payment is exactly the bundle price in the paid asset
+fn bundle_price(bundle: &Bundle, payment: Option<Asset>) -> Result<Asset, ContractError> {
+    let payment = payment.ok_or(ContractError::InvalidPayment {})?;
+    if !bundle.prices.contains(&payment) {
+        return Err(ContractError::InvalidPayment {});
+    }
+    Ok(payment)
+}
+
+// Splits the bundle price by the component shares, the first component gets the rounding remainder
+fn split_bundle_price(bundle: &Bundle, price: &Asset) -> Vec<Asset> {
+    let mut amounts: Vec<Uint128> = bundle
+        .components
+        .iter()
+        .map(|component| price.amount.multiply_ratio(component.share, 100u128))
+        .collect();
+    let distributed: Uint128 = amounts.iter().sum();
+    amounts[0] += price.amount - distributed;
+
+    amounts
+        .into_iter()
+        .map(|amount| Asset {
+            info: price.info.clone(),
+            amount,
+        })
+        .collect()
+}
+
+fn execute_cancel_plan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    // Load the subscription
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+
+    // Check that the sender is the subscriber or the payer of the subscription
+    if info.sender != subscription.subscriber && info.sender != subscription.payer {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the subscription is cancelable
+    if !subscription_plan.cancelable {
+        return Err(ContractError::NotCancelable {});
+    };
+
+    // Bundled subscriptions are managed through their bundle
+    if BUNDLED_SUBSCRIPTIONS.has(deps.storage, subscription_id) {
+        return Err(ContractError::BundledSubscription {});
+    };
+
+    // Check that the subscription is not already canceled
+    if subscription.canceled {
+        return Err(ContractError::AlreadyCanceled {});
+    };
+
+    // Check that the subscription is not expired, paused subscriptions keep their time
+    if subscription.paused_at.is_none() && env.block.time > subscription.expiration {
+        return Err(ContractError::AlreadyExpired {});
+    };
+
+    // Charge the overage used so far before the unused balance is returned
+    let organization_id = subscription_plan.organization_id;
+    let period = usage_period(&subscription_plan, &subscription, &env);
+    let overage = settle_overage(
+        deps.storage,
+        &env,
+        &subscription_plan,
+        subscription_id,
+        &mut subscription,
+        period + 1,
+    )?;
+
+    // Take the unused part of the held payments out of the escrow as the refund
+    let refund = refund_escrow(
+        deps.storage,
+        &env,
+        &subscription_plan,
+        subscription_id,
+        &subscription,
+    )?;
+
+    // Cancel the subscription, save it and free its seat
+    subscription.canceled = true;
+    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    release_seat(deps.storage, subscription_id)?;
+
+    let mut response = Response::new()
+        .add_event(events::subscription_canceled(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+        ))
+        .add_submessages(hook_messages(
+            deps.storage,
+            organization_id,
+            SubscriptionHookMsg::Canceled {
+                subscription_id,
+                plan_id: subscription.plan_id,
+                subscriber: subscription.subscriber.clone(),
+            },
+        )?);
+    if let Some(overage) = overage.filter(|overage| !overage.amount.is_zero()) {
+        response = response.add_event(events::balance_charged(
+            &env,
+            organization_id,
+            subscription_id,
+            &subscription,
+            &overage,
+            "overage",
+        ));
+    }
+
+    // Credit the unused part of refundable subscriptions to the payer
+    if let Some(refund) = refund.filter(|refund| !refund.amount.is_zero()) {
+        let credit_event = add_credit(
+            deps.storage,
+            &env,
+            &subscription.payer,
+            &refund,
+            "refund",
+            Some(subscription_id),
+        )?;
+        response = response
+            .add_event(credit_event)
+            .add_event(events::refund_issued(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+                &refund,
+                "unused_time",
+            ))
+            .add_attribute("refund", refund.to_string());
+    }
+
+    // Credit the unused prepaid balance to the payer
+    if let Some(balance) = withdraw_balance(&mut subscription) {
+        SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+        let credit_event = add_credit(
+            deps.storage,
+            &env,
+            &subscription.payer,
+            &balance,
+            "balance",
+            Some(subscription_id),
+        )?;
+        response = response
+            .add_event(credit_event)
+            .add_event(events::refund_issued(
+                &env,
+                organization_id,
+                subscription_id,
+                &subscription,
+                &balance,
+                "balance",
+            ))
+            .add_attribute("refund", balance.to_string());
+    }
+
+    // Remove the subscription from the subscription plan's list of subscriptions
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.remove(
+        deps.storage,
+        (subscription.plan_id, subscription.subscriber),
+    );
+
+    Ok(response
+        .add_attribute("action", "cancel_plan")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("subscription_plan_id", subscription.plan_id.to_string())
+        .add_attribute("subscription_id", subscription_id.to_string()))
+}
+
+fn execute_deposit_credit(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    payment: Option<Asset>,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    // Check that funds were sent
+    let payment = payment
+        .filter(|payment| !payment.amount.is_zero())
+        .ok_or(ContractError::InvalidPayment {})?;
+
+    // Add the funds to the credit of the recipient
+    let recipient = maybe_addr(deps.api, recipient)?.unwrap_or_else(|| sender.clone());
+    let event = add_credit(deps.storage, &env, &recipient, &payment, "deposit", None)?;
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "deposit_credit")
+        .add_attribute("sender", sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", payment.to_string()))
+}
+
+fn execute_withdraw_credit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: AssetInfo,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    // Withdraw the whole credit of the asset if no amount is given
+    let credit = CREDITS
+        .may_load(deps.storage, (info.sender.clone(), asset.key()))?
+        .map(|credit| credit.amount)
+        .unwrap_or_default();
+    let withdrawal = Asset {
+        info: asset,
+        amount: amount.unwrap_or(credit),
+    };
+    if withdrawal.amount.is_zero() {
+        return Err(ContractError::InsufficientCredit {});
+    }
+    let event = deduct_credit(
+        deps.storage,
+        &env,
+        &info.sender,
+        &withdrawal,
+        "withdrawal",
+        None,
+    )?;
+
+    Ok(Response::new()
+        .add_message(withdrawal.transfer_msg(&info.sender)?)
+        .add_event(event)
+        .add_attribute("action", "withdraw_credit")
+        .add_attribute("amount", withdrawal.to_string()))
+}
+
+// Selects the asset the payer pays with, without a payment the first plan asset the payer has credit in
+fn payment_asset(
+    storage: &dyn Storage,
+    payer: &Addr,
+    subscription_plan: &SubscriptionPlan,
+    payment: Option<&Asset>,
+) -> StdResult<Option<AssetInfo>> {
+    if let Some(payment) = payment {
+        return Ok(Some(payment.info.clone()));
+    }
+
+    let assets: Vec<&AssetInfo> = match &subscription_plan.reference_price {
+        Some(reference_price) => reference_price.assets.iter().collect(),
+        None => subscription_plan
+            .prices
+            .iter()
+            .map(|price| &price.info)
+            .collect(),
+    };
+    for asset in assets {
+        let credit = CREDITS.may_load(storage, (payer.clone(), asset.key()))?;
+        if credit.is_some_and(|credit| !credit.amount.is_zero()) {
+            return Ok(Some(asset.clone()));
+        }
+    }
+    Ok(None)
+}
+
+// Credit of the payer that covers the part of the price the payment does not
+fn credit_to_spend(
+    storage: &dyn Storage,
+    payer: &Addr,
+    price: Option<&Asset>,
+    payment: Option<&Asset>,
+) -> StdResult<Option<Asset>> {
+    let Some(price) = price else {
+        return Ok(None);
+    };
+
+    let paid = payment
+        .filter(|payment| payment.info == price.info)
+        .map(|payment| payment.amount)
+        .unwrap_or_default();
+    let credit = CREDITS
+        .may_load(storage, (payer.clone(), price.info.key()))?
+        .map(|credit| credit.amount)
+        .unwrap_or_default();
+    let amount = price.amount.saturating_sub(paid).min(credit);
+    if amount.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(Asset {
+        info: price.info.clone(),
+        amount,
+    }))
+}
+
+// Returns the part of the price left to pay after the credit and the bound that applies to it
+fn without_credit(
+    price: Option<&Asset>,
+    credit: Option<&Asset>,
+    max_price: Option<Uint128>,
+) -> (Option<Asset>, Option<Uint128>) {
+    let credit = credit.map(|credit| credit.amount).unwrap_or_default();
+    let uncovered = price.map(|price| Asset {
+        info: price.info.clone(),
+        amount: price.amount - credit,
+    });
+    (
+        uncovered,
+        max_price.map(|max_price| max_price.saturating_sub(credit)),
+    )
+}
+
+// Adds the asset to the user's credit and records it in the ledger
+fn add_credit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    asset: &Asset,
+    reason: &str,
+    subscription_id: Option<u64>,
+) -> StdResult<Event> {
+    CREDITS.update(storage, (user.clone(), asset.info.key()), |credit| {
+        let mut credit = credit.unwrap_or(Asset {
+            info: asset.info.clone(),
+            amount: Uint128::zero(),
+        });
+        credit.amount += asset.amount;
+        Ok::<_, StdError>(credit)
+    })?;
+
+    record_credit_entry(
+        storage,
+        env,
+        user,
+        CreditEntryKind::Credit,
+        asset,
+        reason,
+        subscription_id,
+    )
+}
+
+// Deducts the asset from the user's credit and records it in the ledger
+fn deduct_credit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    asset: &Asset,
+    reason: &str,
+    subscription_id: Option<u64>,
+) -> Result<Event, ContractError> {
+    let key = (user.clone(), asset.info.key());
+    let mut credit = CREDITS
+        .may_load(storage, key.clone())?
+        .ok_or(ContractError::InsufficientCredit {})?;
+    credit.amount = credit
+        .amount
+        .checked_sub(asset.amount)
+        .map_err(|_| ContractError::InsufficientCredit {})?;
+    CREDITS.save(storage, key, &credit)?;
+
+    Ok(record_credit_entry(
+        storage,
+        env,
+        user,
+        CreditEntryKind::Debit,
+        asset,
+        reason,
+        subscription_id,
+    )?)
+}
+
+// Appends an entry to the user's credit ledger
+fn record_credit_entry(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    kind: CreditEntryKind,
+    asset: &Asset,
+    reason: &str,
+    subscription_id: Option<u64>,
+) -> StdResult<Event> {
+    let entry_id = CREDIT_LEDGER_ID
+        .may_load(storage, user.clone())?
+        .unwrap_or_default()
+        + 1;
+    CREDIT_LEDGER_ID.save(storage, user.clone(), &entry_id)?;
+
+    let entry = CreditEntry {
+        kind,
+        amount: asset.clone(),
+        reason: reason.to_string(),
+        subscription_id,
+        timestamp: env.block.time,
+    };
+    CREDIT_LEDGER.save(storage, (user.clone(), entry_id), &entry)?;
+
+    Ok(events::credit_updated(env, user, entry_id, &entry))
+}
+
+fn execute_withdraw_revenue(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    asset: AssetInfo,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Withdraw the whole revenue of the asset if no amount is given
+    let revenue = ORGANIZATION_REVENUE
+        .may_load(deps.storage, (organization_id, asset.key()))?
+        .map(|revenue| revenue.amount)
+        .unwrap_or_default();
+    let withdrawal = Asset {
+        info: asset,
+        amount: amount.unwrap_or(revenue),
+    };
+
+    // The accrued shares of the revenue split recipients are paid out as well
+    let payouts = RECIPIENT_REVENUE
+        .sub_prefix(organization_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |((_, key), share)| {
+                *key == withdrawal.info.key() && !share.amount.is_zero()
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    if withdrawal.amount.is_zero() && payouts.is_empty() {
+        return Err(ContractError::InsufficientRevenue {});
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "withdraw_revenue")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("amount", withdrawal.to_string());
+    if !withdrawal.amount.is_zero() {
+        deduct_organization_revenue(deps.storage, organization_id, &withdrawal)?;
+        response = response
+            .add_message(withdrawal.transfer_msg(&organization.owner)?)
+            .add_event(events::revenue_withdrawn(
+                &env,
+                organization_id,
+                &organization.owner,
+                &withdrawal,
+            ));
+    }
+    for ((recipient, key), share) in payouts {
+        RECIPIENT_REVENUE.remove(deps.storage, (organization_id, recipient.clone(), key));
+        response = response
+            .add_message(share.transfer_msg(&recipient)?)
+            .add_event(events::revenue_withdrawn(
+                &env,
+                organization_id,
+                &recipient,
+                &share,
+            ));
+    }
+
+    Ok(response)
+}
+
+fn execute_set_revenue_split(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    shares: Vec<RevenueShare>,
+) -> Result<Response, ContractError> {
+    // Load the organization
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    // Check that the sender is the organization owner
+    if info.sender != organization.owner {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // Check that the recipients are different and the shares do not exceed the whole revenue
+    let total_bps: u32 = shares.iter().map(|share| share.bps as u32).sum();
+    if shares.len() > MAX_REVENUE_RECIPIENTS || total_bps > 10_000 {
+        return Err(ContractError::InvalidRevenueSplit {});
+    }
+    for (index, share) in shares.iter().enumerate() {
+        deps.api.addr_validate(share.recipient.as_str())?;
+        if share.bps == 0
+            || shares[..index]
+                .iter()
+                .any(|other| other.recipient == share.recipient)
+        {
+            return Err(ContractError::InvalidRevenueSplit {});
+        }
+    }
+
+    // Save the revenue split, it applies to the revenue collected from now on
+    if shares.is_empty() {
+        REVENUE_SPLITS.remove(deps.storage, organization_id);
+    } else {
+        REVENUE_SPLITS.save(deps.storage, organization_id, &shares)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_revenue_split")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_event(events::revenue_split_updated(
+            &env,
+            organization_id,
+            &shares,
+        )))
+}
+
+fn execute_claim_revenue(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    organization_id: u32,
+    asset: AssetInfo,
+) -> Result<Response, ContractError> {
+    // Load the accrued share of the sender
+    let key = (organization_id, info.sender.clone(), asset.key());
+    let share = RECIPIENT_REVENUE
+        .may_load(deps.storage, key.clone())?
+        .filter(|share| !share.amount.is_zero())
+        .ok_or(ContractError::InsufficientRevenue {})?;
+
+    // Pay out the whole share
+    RECIPIENT_REVENUE.remove(deps.storage, key);
+
+    Ok(Response::new()
+        .add_message(share.transfer_msg(&info.sender)?)
+        .add_event(events::revenue_withdrawn(
+            &env,
+            organization_id,
+            &info.sender,
+            &share,
+        ))
+        .add_attribute("action", "claim_revenue")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("amount", share.to_string()))
+}
+
+// Splits the revenue between the revenue split recipients, the organization owner keeps the rest
+fn split_revenue(
+    storage: &dyn Storage,
+    organization_id: u32,
+    asset: &Asset,
+) -> StdResult<(Asset, Vec<(Addr, Asset)>)> {
+    let shares: Vec<(Addr, Asset)> = REVENUE_SPLITS
+        .may_load(storage, organization_id)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|share| {
+            let amount = asset.amount.multiply_ratio(share.bps, 10_000u128);
+            (
+                share.recipient,
+                Asset {
+                    info: asset.info.clone(),
+                    amount,
+                },
+            )
+        })
+        .collect();
+    let shared: Uint128 = shares.iter().map(|(_, share)| share.amount).sum();
+    let remainder = Asset {
+        info: asset.info.clone(),
+        amount: asset.amount - shared,
+    };
+    Ok((remainder, shares))
+}
+
+// Adds a subscription payment to the organization's revenue, the referrer earns its commission from
+// it. Payments of refundable plans are held in escrow and only become revenue once they are earned.
+fn add_subscription_revenue(
+    storage: &mut dyn Storage,
+    env: &Env,
+    subscription_plan: &SubscriptionPlan,
+    subscription_id: u64,
+    subscription: &Subscription,
+    payment: &Asset,
+    first_payment: bool,
+) -> StdResult<()> {
+    let commission = match (
+        subscription.referrer.as_ref(),
+        AFFILIATE_REWARDS.may_load(storage, subscription.plan_id)?,
+    ) {
+        (Some(_), Some(reward)) if first_payment || reward.recurring => {
+            payment.amount.multiply_ratio(reward.bps, 10_000u128)
+        }
+        _ => Uint128::zero(),
+    };
+
+    // Only referrals whose first payment earns a commission are counted
+    if let (Some(referrer), true) = (
+        subscription.referrer.as_ref(),
+        first_payment && !commission.is_zero(),
+    ) {
+        REFERRALS.update(storage, referrer.clone(), |referrals| {
+            Ok::<_, StdError>(referrals.unwrap_or_default() + 1)
+        })?;
+    }
+
+    // Pay out the payments of non refundable plans right away
+    if !subscription_plan.refundable {
+        return pay_revenue(
+            storage,
+            subscription_plan.organization_id,
+            subscription.referrer.as_ref(),
+            payment,
+            commission,
+        );
+    }
+
+    // Hold the payment together with the referrer's commission until it is earned
+    ESCROWS.update(storage, (subscription_id, payment.info.key()), |escrow| {
+        let mut escrow = escrow.unwrap_or(Escrow {
+            amount: Asset {
+                info: payment.info.clone(),
+                amount: Uint128::zero(),
+            },
+            commission: Uint128::zero(),
+        });
+        escrow.amount.amount += payment.amount;
+        escrow.commission += commission;
+        Ok::<_, StdError>(escrow)
+    })?;
+    release_escrow(
+        storage,
+        env,
+        subscription_plan,
+        subscription_id,
+        subscription,
+    )
+}
+
+// Credits the commission to the referrer and the rest of the payment to the organization
+fn pay_revenue(
+    storage: &mut dyn Storage,
+    organization_id: u32,
+    referrer: Option<&Addr>,
+    payment: &Asset,
+    commission: Uint128,
+) -> StdResult<()> {
+    let referrer = match referrer {
+        Some(referrer) if !commission.is_zero() => referrer,
+        _ => return add_revenue(storage, organization_id, payment),
+    };
+
+    let key = (referrer.clone(), payment.info.key());
+    for rewards in [REFERRAL_REWARDS, REFERRAL_EARNINGS] {
+        rewards.update(storage, key.clone(), |reward| {
+            let mut reward = reward.unwrap_or(Asset {
+                info: payment.info.clone(),
+                amount: Uint128::zero(),
+            });
+            reward.amount += commission;
+            Ok::<_, StdError>(reward)
+        })?;
+    }
+
+    add_revenue(
+        storage,
+        organization_id,
+        &Asset {
+            info: payment.info.clone(),
+            amount: payment.amount - commission,
+        },
+    )
+}
+
+// Releases the held payments of a subscription to the organization and the referrer down to the
+// part a cancelation would refund now, so the escrow always covers the refund
+fn release_escrow(
+    storage: &mut dyn Storage,
+    env: &Env,
+    subscription_plan: &SubscriptionPlan,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> StdResult<()> {
+    let refundable = match &subscription.price {
+        Some(price) if subscription_plan.refundable && !subscription.canceled => {
+            Some(refundable_price(
+                storage,
+                subscription_plan,
+                subscription_id,
+                subscription,
+                price,
+                env,
+            )?)
+        }
+        _ => None,
+    };
+
+    let escrows = ESCROWS
+        .prefix(subscription_id)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Escrow)>>>()?;
+    for (key, mut escrow) in escrows {
+        let held = refundable
+            .as_ref()
+            .filter(|refundable| refundable.info == escrow.amount.info)
+            .map(|refundable| refundable.amount.min(escrow.amount.amount))
+            .unwrap_or_default();
+        let released = Asset {
+            info: escrow.amount.info.clone(),
+            amount: escrow.amount.amount - held,
+        };
+        if released.amount.is_zero() {
+            continue;
+        }
+
+        // The commission is released in proportion to the released payment
+        let commission = escrow
+            .commission
+            .multiply_ratio(released.amount, escrow.amount.amount);
+        pay_revenue(
+            storage,
+            subscription_plan.organization_id,
+            subscription.referrer.as_ref(),
+            &released,
+            commission,
+        )?;
+        escrow.amount.amount = held;
+        escrow.commission -= commission;
+        if escrow.amount.amount.is_zero() {
+            ESCROWS.remove(storage, (subscription_id, key));
+        } else {
+            ESCROWS.save(storage, (subscription_id, key), &escrow)?;
+        }
+    }
+    Ok(())
+}
+
+// Returns the part of the price a cancelation would refund now, bundled subscriptions are
+// paid for the period of their bundle
+fn refundable_price(
+    storage: &dyn Storage,
+    subscription_plan: &SubscriptionPlan,
+    subscription_id: u64,
+    subscription: &Subscription,
+    price: &Asset,
+    env: &Env,
+) -> StdResult<Asset> {
+    let Some(bundle_subscription_id) = BUNDLED_SUBSCRIPTIONS.may_load(storage, subscription_id)?
+    else {
+        return Ok(unused_price(subscription_plan, subscription, price, env));
+    };
+
+    let bundle_subscription = BUNDLE_SUBSCRIPTIONS.load(storage, bundle_subscription_id)?;
+    let bundle = BUNDLES.load(storage, bundle_subscription.bundle_id)?;
+    let period = period_seconds(bundle.duration, &bundle.duration_unit);
+    Ok(Asset {
+        info: price.info.clone(),
+        amount: price
+            .amount
+            .multiply_ratio(remaining_seconds(subscription, env), period),
+    })
+}
+
+// Releases what the subscription earned and takes the rest of its escrow out as the refund, the
+// commission on the refunded part is never paid to the referrer
+fn refund_escrow(
+    storage: &mut dyn Storage,
+    env: &Env,
+    subscription_plan: &SubscriptionPlan,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> StdResult<Option<Asset>> {
+    release_escrow(
+        storage,
+        env,
+        subscription_plan,
+        subscription_id,
+        subscription,
+    )?;
+
+    let Some(price) = &subscription.price else {
+        return Ok(None);
+    };
+    let key = (subscription_id, price.info.key());
+    let refund = ESCROWS.may_load(storage, key.clone())?;
+    ESCROWS.remove(storage, key);
+    Ok(refund.map(|escrow| escrow.amount))
+}
+
+// Adds the asset to the organization's revenue, the revenue split recipients accrue their shares
+fn add_revenue(storage: &mut dyn Storage, organization_id: u32, asset: &Asset) -> StdResult<()> {
+    let (remainder, shares) = split_revenue(storage, organization_id, asset)?;
+    for (recipient, share) in shares {
+        RECIPIENT_REVENUE.update(
+            storage,
+            (organization_id, recipient, asset.info.key()),
+            |revenue| {
+                let mut revenue = revenue.unwrap_or(Asset {
+                    info: asset.info.clone(),
+                    amount: Uint128::zero(),
+                });
+                revenue.amount += share.amount;
+                Ok::<_, StdError>(revenue)
+            },
+        )?;
+    }
+    ORGANIZATION_REVENUE.update(storage, (organization_id, asset.info.key()), |revenue| {
+        let mut revenue = revenue.unwrap_or(Asset {
+            info: asset.info.clone(),
+            amount: Uint128::zero(),
+        });
+        revenue.amount += remainder.amount;
+        Ok::<_, StdError>(revenue)
+    })?;
+    Ok(())
+}
+
+// Deducts the asset from the revenue the organization owner can withdraw
+fn deduct_organization_revenue(
+    storage: &mut dyn Storage,
+    organization_id: u32,
+    asset: &Asset,
+) -> Result<(), ContractError> {
+    let key = (organization_id, asset.info.key());
+    let mut revenue = ORGANIZATION_REVENUE
+        .may_load(storage, key.clone())?
+        .ok_or(ContractError::InsufficientRevenue {})?;
+    revenue.amount = revenue
+        .amount
+        .checked_sub(asset.amount)
+        .map_err(|_| ContractError::InsufficientRevenue {})?;
+    ORGANIZATION_REVENUE.save(storage, key, &revenue)?;
+    Ok(())
+}
+
+// Length of a single period of the subscription plan in seconds
+fn plan_period_seconds(subscription_plan: &SubscriptionPlan) -> u64 {
+    period_seconds(subscription_plan.duration, &subscription_plan.duration_unit)
+}
+
+// Length of the given duration in seconds
+fn period_seconds(duration: u8, duration_unit: &DurationUnit) -> u64 {
+    let time_unit = match duration_unit {
+        DurationUnit::Day => 86400,
+        DurationUnit::Week => 604800,
+        DurationUnit::Month => 2592000,
+        DurationUnit::Year => 31536000,
+    };
+    duration as u64 * time_unit
 }
 
-fn execute_cancel_plan(
+fn execute_transfer_subscription(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     subscription_id: u64,
+    recipient: String,
 ) -> Result<Response, ContractError> {
+    // Validate recipient address
+    let recipient = deps.api.addr_validate(&recipient)?;
+
     // Load the subscription
     let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
 
-    // Load the subscription plan
-    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
-
     // Check that the sender is the subscription owner
     if info.sender != subscription.subscriber {
         return Err(ContractError::Unauthorized {});
     };
 
-    // Check that the subscription is cancelable
-    if !subscription_plan.cancelable {
-        return Err(ContractError::NotCancelable {});
+    let event = transfer_subscription(
+        deps.storage,
+        &env,
+        subscription_id,
+        &mut subscription,
+        &recipient,
+    )?;
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "transfer_subscription")
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("subscription_plan_id", subscription.plan_id.to_string())
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient))
+}
+
+// Moves an active subscription and all of its indexes to the recipient
+pub(crate) fn transfer_subscription(
+    storage: &mut dyn Storage,
+    env: &Env,
+    subscription_id: u64,
+    subscription: &mut Subscription,
+    recipient: &Addr,
+) -> Result<Event, ContractError> {
+    // Load the subscription plan
+    let subscription_plan = SUBSCRIPTION_PLANS.load(storage, subscription.plan_id)?;
+
+    // Check that the subscription plan allows transfers
+    if !subscription_plan.transferable {
+        return Err(ContractError::NotTransferable {});
     };
 
-    // Check that the subscription is not already canceled
-    if subscription.canceled {
-        return Err(ContractError::AlreadyCanceled {});
+    // Bundled subscriptions are managed through their bundle
+    if BUNDLED_SUBSCRIPTIONS.has(storage, subscription_id) {
+        return Err(ContractError::BundledSubscription {});
     };
 
-    // Check that the subscription is not expired
-    if env.block.time > subscription.expiration {
-        return Err(ContractError::AlreadyExpired {});
+    // Check that the subscription is still active
+    if !is_active(subscription, env) {
+        return Err(ContractError::NotTransferable {});
     };
 
-    // Cancel the subscription and save it
-    subscription.canceled = true;
-    SUBSCRIPTIONS.save(deps.storage, subscription_id, &subscription)?;
+    // Check that the recipient does not already have a subscription to the plan
+    if let Some(recipient_subscription_id) =
+        USER_SUBSCRIPTIONS.may_load(storage, (recipient.clone(), subscription.plan_id))?
+    {
+        let recipient_subscription = SUBSCRIPTIONS.load(storage, recipient_subscription_id)?;
+        if is_held(&recipient_subscription, env) {
+            return Err(ContractError::AlreadySubscribed {});
+        }
 
-    // Remove the subscription from the user's list of subscriptions
-    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.remove(deps.storage, (subscription.plan_id, info.sender));
+        // The replaced subscription gives up its seat
+        release_seat(storage, recipient_subscription_id)?;
+    }
 
-    Ok(Response::new()
-        .add_attribute("action", "cancel_plan")
-        .add_attribute("subscription_id", subscription_id.to_string()))
+    // Move the subscription indexes from the current owner to the recipient
+    let owner = subscription.subscriber.clone();
+    USER_SUBSCRIPTIONS.remove(storage, (owner.clone(), subscription.plan_id));
+    USER_SUBSCRIPTIONS.save(
+        storage,
+        (recipient.clone(), subscription.plan_id),
+        &subscription_id,
+    )?;
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.remove(storage, (subscription.plan_id, owner.clone()));
+    SUBSCRIPTION_PLAN_SUBSCRIPTIONS.save(
+        storage,
+        (subscription.plan_id, recipient.clone()),
+        &subscription_id,
+    )?;
+    OWNER_TOKENS.remove(storage, (owner.clone(), subscription_id));
+    OWNER_TOKENS.save(storage, (recipient.clone(), subscription_id), &true)?;
+
+    // Approvals of the subscription token do not carry over to the new owner
+    TOKEN_APPROVALS.remove(storage, subscription_id);
+
+    // Update the subscription owner and save it, the recipient also becomes the payer so the
+    // previous owner or payer can no longer cancel it and collect its refund
+    subscription.subscriber = recipient.clone();
+    subscription.payer = recipient.clone();
+    SUBSCRIPTIONS.save(storage, subscription_id, subscription)?;
+
+    Ok(events::subscription_transferred(
+        env,
+        subscription_plan.organization_id,
+        subscription_id,
+        subscription,
+        &owner,
+    ))
+}
+
+// The part of the price paid for the subscription that was not used yet
+fn unused_price(
+    subscription_plan: &SubscriptionPlan,
+    subscription: &Subscription,
+    price: &Asset,
+    env: &Env,
+) -> Asset {
+    let amount = match subscription_plan.kind {
+        // Lifetime subscriptions are used as soon as they are purchased
+        PlanKind::Lifetime => Uint128::zero(),
+        // Access passes refund their remaining uses
+        PlanKind::AccessPass { uses } => price
+            .amount
+            .multiply_ratio(subscription.remaining_uses.unwrap_or_default(), uses),
+        // Installments refund what was paid beyond the used time
+        PlanKind::Installments { .. } => {
+            let duration = plan_period_seconds(subscription_plan);
+            let used_seconds = env.block.time.seconds() - subscription.started.seconds();
+            let paid = subscription
+                .installments
+                .as_ref()
+                .map(|installments| installments.paid())
+                .unwrap_or_default();
+            paid.saturating_sub(
+                price
+                    .amount
+                    .multiply_ratio(used_seconds.min(duration), duration),
+            )
+        }
+        // All of the unused time is refunded, including periods renewed in advance
+        _ => price.amount.multiply_ratio(
+            remaining_seconds(subscription, env),
+            plan_period_seconds(subscription_plan),
+        ),
+    };
+    Asset {
+        info: price.info.clone(),
+        amount,
+    }
+}
+
+// Checks if the subscription is neither canceled, expired, used up, suspended nor paused
+pub(crate) fn is_active(subscription: &Subscription, env: &Env) -> bool {
+    !subscription.canceled
+        && grace_end(subscription) >= env.block.time
+        && subscription.remaining_uses != Some(0)
+        && matches!(
+            subscription.status,
+            SubscriptionStatus::Active | SubscriptionStatus::PastDue
+        )
+        && !subscription
+            .installments
+            .as_ref()
+            .is_some_and(|installments| {
+                installments.is_overdue(subscription.started, env.block.time)
+            })
+}
+
+// Checks if the subscription still holds its place in the plan, paused and suspended
+// subscriptions do even though they give no access
+fn is_held(subscription: &Subscription, env: &Env) -> bool {
+    if subscription.canceled || subscription.remaining_uses == Some(0) {
+        return false;
+    }
+    match subscription.status {
+        SubscriptionStatus::Paused => true,
+        SubscriptionStatus::Lapsed => false,
+        _ => grace_end(subscription) >= env.block.time,
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
         QueryMsg::Organization { organization_id } => {
             to_binary(&query_organization(deps, organization_id)?)
         }
+        QueryMsg::Organizations {
+            verified,
+            start_after,
+            limit,
+        } => to_binary(&query_organizations(deps, verified, start_after, limit)?),
+        QueryMsg::OrganizationByHandle { handle } => {
+            to_binary(&query_organization_by_handle(deps, handle)?)
+        }
         QueryMsg::UserOrganizations { user_address } => {
             to_binary(&query_user_organizations(deps, user_address)?)
         }
@@ -352,18 +5092,213 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             user_address,
             plan_id,
         } => to_binary(&query_is_subscribed(deps, env, user_address, plan_id)?),
+        QueryMsg::HasEntitlement {
+            user_address,
+            organization_id,
+            feature,
+        } => to_binary(&query_has_entitlement(
+            deps,
+            env,
+            user_address,
+            organization_id,
+            feature,
+        )?),
+        QueryMsg::OrganizationRevenue { organization_id } => {
+            to_binary(&query_organization_revenue(deps, organization_id)?)
+        }
+        QueryMsg::Credits { user_address } => to_binary(&query_credits(deps, user_address)?),
+        QueryMsg::CreditLedger {
+            user_address,
+            start_after,
+            limit,
+        } => to_binary(&query_credit_ledger(
+            deps,
+            user_address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::RevenueSplit { organization_id } => to_binary(
+            &REVENUE_SPLITS
+                .may_load(deps.storage, organization_id)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::RecipientRevenue { organization_id } => {
+            to_binary(&query_recipient_revenue(deps, organization_id)?)
+        }
+        QueryMsg::Escrow { subscription_id } => to_binary(&query_escrow(deps, subscription_id)?),
+        QueryMsg::Usage {
+            subscription_id,
+            start_after,
+            limit,
+        } => to_binary(&query_usage(
+            deps,
+            env,
+            subscription_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Installments { subscription_id } => {
+            to_binary(&query_installments(deps, env, subscription_id)?)
+        }
+        QueryMsg::UsageReporters { organization_id } => to_binary(
+            &USAGE_REPORTERS
+                .may_load(deps.storage, organization_id)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::PlanCapacity { plan_id } => to_binary(&query_plan_capacity(deps, plan_id)?),
+        QueryMsg::Waitlist {
+            plan_id,
+            start_after,
+            limit,
+        } => to_binary(&query_waitlist(deps, plan_id, start_after, limit)?),
+        QueryMsg::PlanAccess { plan_id } => to_binary(
+            &PLAN_ACCESS
+                .may_load(deps.storage, plan_id)?
+                .unwrap_or(PlanAccess::Public),
+        ),
+        QueryMsg::Bundle { bundle_id } => to_binary(&BUNDLES.load(deps.storage, bundle_id)?),
+        QueryMsg::BundleSubscription {
+            bundle_subscription_id,
+        } => to_binary(&BUNDLE_SUBSCRIPTIONS.load(deps.storage, bundle_subscription_id)?),
+        QueryMsg::AffiliateReward { plan_id } => {
+            to_binary(&AFFILIATE_REWARDS.may_load(deps.storage, plan_id)?)
+        }
+        QueryMsg::ReferrerStats { referrer } => to_binary(&query_referrer_stats(deps, referrer)?),
+        QueryMsg::TokenGate { plan_id } => to_binary(&TOKEN_GATES.may_load(deps.storage, plan_id)?),
+        QueryMsg::PlanAllowlist {
+            plan_id,
+            start_after,
+            limit,
+        } => to_binary(&query_plan_allowlist(deps, plan_id, start_after, limit)?),
+        QueryMsg::Verifiers {} => to_binary(&VERIFIERS.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::Hooks { organization_id } => to_binary(
+            &HOOKS
+                .may_load(deps.storage, organization_id)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::CouponInfo {
+            organization_id,
+            code,
+        } => to_binary(&query_coupon_info(deps, env, organization_id, code)?),
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => to_binary(&cw721::query_owner_of(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => to_binary(&cw721::query_approval(
+            deps,
+            env,
+            token_id,
+            spender,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => to_binary(&cw721::query_approvals(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => to_binary(&cw721::query_all_operators(
+            deps,
+            env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NumTokens {} => to_binary(&cw721::query_num_tokens(deps)?),
+        QueryMsg::ContractInfo {} => to_binary(&cw721::query_contract_info()),
+        QueryMsg::NftInfo { token_id } => to_binary(&cw721::query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_binary(&cw721::query_all_nft_info(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&cw721::query_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            to_binary(&cw721::query_all_tokens(deps, start_after, limit)?)
+        }
     }
 }
 
-fn query_organization(deps: Deps, organization_id: u32) -> StdResult<OrganizationResponse> {
-    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+// Attaches the verification of the organization
+fn organization_response(
+    storage: &dyn Storage,
+    organization_id: u32,
+    organization: Organization,
+) -> StdResult<OrganizationResponse> {
+    let verification = VERIFICATIONS.may_load(storage, organization_id)?;
 
     Ok(OrganizationResponse {
         id: organization_id,
         data: organization,
+        verified: verification.is_some(),
+        verification,
     })
 }
 
+fn query_organization(deps: Deps, organization_id: u32) -> StdResult<OrganizationResponse> {
+    let organization = ORGANIZATIONS.load(deps.storage, organization_id)?;
+
+    organization_response(deps.storage, organization_id, organization)
+}
+
+fn query_organizations(
+    deps: Deps,
+    verified: Option<bool>,
+    start_after: Option<u32>,
+    limit: Option<u8>,
+) -> StdResult<Vec<OrganizationResponse>> {
+    let limit = limit.unwrap_or(20) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    // Load the organizations matching the verification filter
+    ORGANIZATIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|item| {
+            let (organization_id, organization) = item?;
+            organization_response(deps.storage, organization_id, organization)
+        })
+        .filter(|res| match (res, verified) {
+            (Ok(res), Some(verified)) => res.verified == verified,
+            _ => true,
+        })
+        .take(limit)
+        .collect()
+}
+
+fn query_organization_by_handle(deps: Deps, handle: String) -> StdResult<OrganizationResponse> {
+    let handle = validation::normalize_handle(&handle)
+        .map_err(|_| StdError::generic_err("Invalid handle"))?;
+    let organization_id = ORGANIZATION_HANDLES.load(deps.storage, &handle)?;
+
+    query_organization(deps, organization_id)
+}
+
 fn query_user_organizations(
     deps: Deps,
     user_address: String,
@@ -379,10 +5314,7 @@ fn query_user_organizations(
         .iter()
         .map(|id| {
             let organization = ORGANIZATIONS.load(deps.storage, *id)?;
-            Ok(OrganizationResponse {
-                id: *id,
-                data: organization,
-            })
+            organization_response(deps.storage, *id, organization)
         })
         .collect::<StdResult<Vec<OrganizationResponse>>>()?;
 
@@ -430,6 +5362,50 @@ fn query_subscription(deps: Deps, subscription_id: u64) -> StdResult<Subscriptio
     })
 }
 
+fn query_plan_capacity(deps: Deps, plan_id: u64) -> StdResult<PlanCapacityResponse> {
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+
+    Ok(PlanCapacityResponse {
+        max_subscribers: subscription_plan.max_subscribers,
+        active_subscribers: ACTIVE_SUBSCRIBERS
+            .may_load(deps.storage, plan_id)?
+            .unwrap_or_default(),
+    })
+}
+
+fn query_waitlist(
+    deps: Deps,
+    plan_id: u64,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<Vec<WaitlistEntry>> {
+    let limit = limit.unwrap_or(20) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    WAITLIST
+        .prefix(plan_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(position, user)| WaitlistEntry { position, user }))
+        .collect()
+}
+
+fn query_plan_allowlist(
+    deps: Deps,
+    plan_id: u64,
+    start_after: Option<String>,
+    limit: Option<u8>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(20) as usize;
+    let start = maybe_addr(deps.api, start_after)?.map(Bound::exclusive);
+
+    PLAN_ALLOWLIST
+        .prefix(plan_id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
 fn query_user_subscriptions(
     deps: Deps,
     user_address: String,
@@ -497,17 +5473,238 @@ fn query_is_subscribed(
     // Validate user address
     let user_addr = deps.api.addr_validate(&user_address)?;
 
-    // Load subscription
-    let subscription = SUBSCRIPTIONS.load(deps.storage, plan_id)?;
+    // Load the user's subscription to the plan
+    let subscription_id = match USER_SUBSCRIPTIONS.may_load(deps.storage, (user_addr, plan_id))? {
+        Some(subscription_id) => subscription_id,
+        None => return Ok(false),
+    };
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    Ok(is_active(&subscription, &env))
+}
+
+fn query_has_entitlement(
+    deps: Deps,
+    env: Env,
+    user_address: String,
+    organization_id: u32,
+    feature: String,
+) -> StdResult<bool> {
+    // Validate user address
+    let user_addr = deps.api.addr_validate(&user_address)?;
+
+    // Look for an active subscription to a plan of the organization with the feature
+    for item in
+        USER_SUBSCRIPTIONS
+            .prefix(user_addr)
+            .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (plan_id, subscription_id) = item?;
+        let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, plan_id)?;
+        if subscription_plan.organization_id != organization_id
+            || !subscription_plan
+                .features
+                .as_ref()
+                .is_some_and(|features| features.contains(&feature))
+        {
+            continue;
+        }
+
+        let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+        if is_active(&subscription, &env) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn query_organization_revenue(deps: Deps, organization_id: u32) -> StdResult<Vec<Asset>> {
+    ORGANIZATION_REVENUE
+        .prefix(organization_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, revenue)| revenue))
+        .collect()
+}
+
+fn query_escrow(deps: Deps, subscription_id: u64) -> StdResult<Vec<Asset>> {
+    ESCROWS
+        .prefix(subscription_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, escrow)| escrow.amount))
+        .collect()
+}
+
+fn query_recipient_revenue(
+    deps: Deps,
+    organization_id: u32,
+) -> StdResult<Vec<RecipientRevenueResponse>> {
+    // Group the accrued shares by recipient
+    let mut recipients: Vec<RecipientRevenueResponse> = vec![];
+    for item in RECIPIENT_REVENUE.sub_prefix(organization_id).range(
+        deps.storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let ((recipient, _), share) = item?;
+        if share.amount.is_zero() {
+            continue;
+        }
+        match recipients.last_mut() {
+            Some(last) if last.recipient == recipient => last.claimable.push(share),
+            _ => recipients.push(RecipientRevenueResponse {
+                recipient,
+                claimable: vec![share],
+            }),
+        }
+    }
+    Ok(recipients)
+}
+
+fn query_referrer_stats(deps: Deps, referrer: String) -> StdResult<ReferrerStatsResponse> {
+    // Validate referrer address
+    let referrer = deps.api.addr_validate(&referrer)?;
+
+    let referrals = REFERRALS
+        .may_load(deps.storage, referrer.clone())?
+        .unwrap_or_default();
+    let earned = REFERRAL_EARNINGS
+        .prefix(referrer.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, earned)| earned))
+        .collect::<StdResult<Vec<Asset>>>()?;
+    let claimable = REFERRAL_REWARDS
+        .prefix(referrer)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, reward)| reward))
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    Ok(ReferrerStatsResponse {
+        referrals,
+        earned,
+        claimable,
+    })
+}
+
+fn query_credits(deps: Deps, user_address: String) -> StdResult<Vec<Asset>> {
+    // Validate user address
+    let user_addr = deps.api.addr_validate(&user_address)?;
+
+    CREDITS
+        .prefix(user_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter_map(|item| {
+            item.map(|(_, credit)| Some(credit).filter(|credit| !credit.amount.is_zero()))
+                .transpose()
+        })
+        .collect()
+}
+
+fn query_credit_ledger(
+    deps: Deps,
+    user_address: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<Vec<CreditEntryResponse>> {
+    // Validate user address
+    let user_addr = deps.api.addr_validate(&user_address)?;
+    let limit = limit.unwrap_or(20) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    CREDIT_LEDGER
+        .prefix(user_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, data)| CreditEntryResponse { id, data }))
+        .collect()
+}
+
+fn query_usage(
+    deps: Deps,
+    env: Env,
+    subscription_id: u64,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<UsageResponse> {
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+    let period_seconds = plan_period_seconds(&subscription_plan);
+    let limit = limit.unwrap_or(20) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    // Load the usage of each period with its time range
+    let periods = USAGE
+        .prefix(subscription_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (period, usage) = item?;
+            let start = subscription.started.plus_seconds(period * period_seconds);
+            Ok(UsagePeriod {
+                period,
+                start,
+                end: start.plus_seconds(period_seconds),
+                units: usage.units,
+                cost: usage.cost,
+                unpaid: usage.unpaid,
+                settled: usage.settled,
+            })
+        })
+        .collect::<StdResult<Vec<UsagePeriod>>>()?;
+
+    Ok(UsageResponse {
+        subscription_id,
+        current_period: usage_period(&subscription_plan, &subscription, &env),
+        balance: subscription.balance,
+        periods,
+    })
+}
 
-    // Check if user is subscribed to the plan
-    let is_subscribed = subscription.subscriber == user_addr;
+fn query_installments(
+    deps: Deps,
+    env: Env,
+    subscription_id: u64,
+) -> StdResult<InstallmentsResponse> {
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+    let schedule = subscription
+        .installments
+        .ok_or_else(|| StdError::not_found("InstallmentSchedule"))?;
+    let paid = schedule.paid();
 
-    // Check if the subscription is canceled
-    let is_canceled = subscription.canceled;
+    Ok(InstallmentsResponse {
+        subscription_id,
+        status: subscription.status,
+        paid: Asset {
+            info: schedule.total.info.clone(),
+            amount: paid,
+        },
+        outstanding: Asset {
+            info: schedule.total.info.clone(),
+            amount: schedule.total.amount - paid,
+        },
+        next_installment: schedule.next_installment(),
+        next_due: schedule.next_due(subscription.started),
+        overdue: schedule.is_overdue(subscription.started, env.block.time),
+        schedule,
+    })
+}
 
-    // Check if the subscription is expired
-    let is_expired = subscription.expiration < env.block.time;
+fn query_coupon_info(
+    deps: Deps,
+    env: Env,
+    organization_id: u32,
+    code: String,
+) -> StdResult<CouponResponse> {
+    let code_hash = hash_coupon_code(&env.contract.address, organization_id, &code);
+    let coupon = COUPONS.load(deps.storage, (organization_id, code_hash.clone()))?;
 
-    Ok(is_subscribed && !is_canceled && !is_expired)
+    Ok(CouponResponse {
+        organization_id,
+        code_hash,
+        remaining_redemptions: coupon
+            .max_redemptions
+            .map(|max_redemptions| max_redemptions.saturating_sub(coupon.redemptions)),
+        data: coupon,
+    })
 }
@@ -0,0 +1,504 @@
+use cosmwasm_std::{
+    Addr, Binary, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+};
+use cw_storage_plus::Bound;
+use cw_utils::{maybe_addr, Expiration};
+
+use crate::contract::transfer_subscription;
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, ContractInfoResponse, Cw721Approval,
+    Cw721ReceiveMsg, NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
+    SubscriptionMetadata, TokensResponse,
+};
+use crate::state::{
+    Approval, Subscription, OPERATORS, ORGANIZATIONS, OWNER_TOKENS, SUBSCRIPTIONS, SUBSCRIPTION_ID,
+    SUBSCRIPTION_PLANS, TOKEN_APPROVALS,
+};
+
+// Collection information of the subscription tokens
+const COLLECTION_NAME: &str = "Subscription Hub";
+const COLLECTION_SYMBOL: &str = "SUBHUB";
+
+// Pagination limits of the list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+// Every subscription is a token and the token id is the subscription id
+fn parse_token_id(token_id: &str) -> StdResult<u64> {
+    token_id
+        .parse::<u64>()
+        .map_err(|_| StdError::not_found("subscription_hub::state::Subscription"))
+}
+
+pub fn execute_transfer_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    // Validate recipient address
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    // Load the subscription
+    let subscription_id = parse_token_id(&token_id)?;
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Check that the sender can transfer the subscription token
+    check_can_send(
+        deps.as_ref(),
+        &env,
+        &info.sender,
+        subscription_id,
+        &subscription,
+    )?;
+
+    let event = transfer_subscription(
+        deps.storage,
+        &env,
+        subscription_id,
+        &mut subscription,
+        &recipient,
+    )?;
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "transfer_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_send_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    // Validate contract address
+    let contract = deps.api.addr_validate(&contract)?;
+
+    // Load the subscription
+    let subscription_id = parse_token_id(&token_id)?;
+    let mut subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Check that the sender can transfer the subscription token
+    check_can_send(
+        deps.as_ref(),
+        &env,
+        &info.sender,
+        subscription_id,
+        &subscription,
+    )?;
+
+    let event = transfer_subscription(
+        deps.storage,
+        &env,
+        subscription_id,
+        &mut subscription,
+        &contract,
+    )?;
+
+    // Notify the receiving contract
+    let receive_msg = Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    };
+
+    Ok(Response::new()
+        .add_message(receive_msg.into_cosmos_msg(contract.to_string())?)
+        .add_event(event)
+        .add_attribute("action", "send_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    // Validate spender address
+    let spender = deps.api.addr_validate(&spender)?;
+
+    // Load the subscription
+    let subscription_id = parse_token_id(&token_id)?;
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Check that the sender is the owner or an operator of the owner
+    check_can_approve(deps.as_ref(), &env, &info.sender, &subscription)?;
+
+    // Check that the approval is not already expired
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    // Replace any existing approval of the spender
+    let mut approvals = TOKEN_APPROVALS
+        .may_load(deps.storage, subscription_id)?
+        .unwrap_or_default();
+    approvals.retain(|approval| approval.spender != spender);
+    approvals.push(Approval {
+        spender: spender.clone(),
+        expires,
+    });
+    TOKEN_APPROVALS.save(deps.storage, subscription_id, &approvals)?;
+
+    Ok(Response::new()
+        .add_event(events::token_approved(
+            &env,
+            subscription_id,
+            &subscription.subscriber,
+            &spender,
+            &expires,
+        ))
+        .add_attribute("action", "approve")
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    // Validate spender address
+    let spender = deps.api.addr_validate(&spender)?;
+
+    // Load the subscription
+    let subscription_id = parse_token_id(&token_id)?;
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // Check that the sender is the owner or an operator of the owner
+    check_can_approve(deps.as_ref(), &env, &info.sender, &subscription)?;
+
+    // Remove the approval of the spender
+    let mut approvals = TOKEN_APPROVALS
+        .may_load(deps.storage, subscription_id)?
+        .unwrap_or_default();
+    approvals.retain(|approval| approval.spender != spender);
+    TOKEN_APPROVALS.save(deps.storage, subscription_id, &approvals)?;
+
+    Ok(Response::new()
+        .add_event(events::token_approval_revoked(
+            &env,
+            subscription_id,
+            &subscription.subscriber,
+            &spender,
+        ))
+        .add_attribute("action", "revoke")
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_approve_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    // Validate operator address
+    let operator = deps.api.addr_validate(&operator)?;
+
+    // Check that the approval is not already expired
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    OPERATORS.save(
+        deps.storage,
+        (info.sender.clone(), operator.clone()),
+        &expires,
+    )?;
+
+    Ok(Response::new()
+        .add_event(events::operator_approved(
+            &env,
+            &info.sender,
+            &operator,
+            &expires,
+        ))
+        .add_attribute("action", "approve_all")
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn execute_revoke_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    // Validate operator address
+    let operator = deps.api.addr_validate(&operator)?;
+
+    OPERATORS.remove(deps.storage, (info.sender.clone(), operator.clone()));
+
+    Ok(Response::new()
+        .add_event(events::operator_revoked(&env, &info.sender, &operator))
+        .add_attribute("action", "revoke_all")
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+// Checks if the sender is an operator of the owner with an unexpired approval
+fn is_operator(deps: Deps, env: &Env, owner: &Addr, sender: &Addr) -> StdResult<bool> {
+    let operator = OPERATORS.may_load(deps.storage, (owner.clone(), sender.clone()))?;
+    Ok(matches!(operator, Some(expires) if !expires.is_expired(&env.block)))
+}
+
+fn check_can_approve(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    subscription: &Subscription,
+) -> Result<(), ContractError> {
+    if *sender == subscription.subscriber
+        || is_operator(deps, env, &subscription.subscriber, sender)?
+    {
+        return Ok(());
+    }
+    Err(ContractError::Unauthorized {})
+}
+
+fn check_can_send(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> Result<(), ContractError> {
+    if check_can_approve(deps, env, sender, subscription).is_ok() {
+        return Ok(());
+    }
+
+    // Check if the sender has an unexpired approval for the subscription token
+    let approvals = TOKEN_APPROVALS
+        .may_load(deps.storage, subscription_id)?
+        .unwrap_or_default();
+    if approvals
+        .iter()
+        .any(|approval| approval.spender == *sender && !approval.expires.is_expired(&env.block))
+    {
+        return Ok(());
+    }
+
+    Err(ContractError::Unauthorized {})
+}
+
+fn humanize_approvals(
+    approvals: Vec<Approval>,
+    block: &BlockInfo,
+    include_expired: bool,
+) -> Vec<Cw721Approval> {
+    approvals
+        .into_iter()
+        .filter(|approval| include_expired || !approval.expires.is_expired(block))
+        .map(|approval| Cw721Approval {
+            spender: approval.spender.to_string(),
+            expires: approval.expires,
+        })
+        .collect()
+}
+
+pub fn query_owner_of(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<OwnerOfResponse> {
+    let subscription_id = parse_token_id(&token_id)?;
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+    let approvals = TOKEN_APPROVALS
+        .may_load(deps.storage, subscription_id)?
+        .unwrap_or_default();
+
+    Ok(OwnerOfResponse {
+        owner: subscription.subscriber.to_string(),
+        approvals: humanize_approvals(approvals, &env.block, include_expired),
+    })
+}
+
+pub fn query_approval(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    spender: String,
+    include_expired: bool,
+) -> StdResult<ApprovalResponse> {
+    let subscription_id = parse_token_id(&token_id)?;
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+
+    // The owner is always approved
+    if subscription.subscriber == spender {
+        return Ok(ApprovalResponse {
+            approval: Cw721Approval {
+                spender,
+                expires: Expiration::Never {},
+            },
+        });
+    }
+
+    let approvals = TOKEN_APPROVALS
+        .may_load(deps.storage, subscription_id)?
+        .unwrap_or_default();
+    humanize_approvals(approvals, &env.block, include_expired)
+        .into_iter()
+        .find(|approval| approval.spender == spender)
+        .map(|approval| ApprovalResponse { approval })
+        .ok_or_else(|| StdError::not_found("Approval not found"))
+}
+
+pub fn query_approvals(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<ApprovalsResponse> {
+    let subscription_id = parse_token_id(&token_id)?;
+    let approvals = TOKEN_APPROVALS
+        .may_load(deps.storage, subscription_id)?
+        .unwrap_or_default();
+
+    Ok(ApprovalsResponse {
+        approvals: humanize_approvals(approvals, &env.block, include_expired),
+    })
+}
+
+pub fn query_all_operators(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorsResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.map(Bound::exclusive);
+
+    let operators = OPERATORS
+        .prefix(owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map(|(_, expires)| include_expired || !expires.is_expired(&env.block))
+                .unwrap_or(true)
+        })
+        .take(limit)
+        .map(|item| {
+            let (operator, expires) = item?;
+            Ok(Cw721Approval {
+                spender: operator.to_string(),
+                expires,
+            })
+        })
+        .collect::<StdResult<Vec<Cw721Approval>>>()?;
+
+    Ok(OperatorsResponse { operators })
+}
+
+pub fn query_num_tokens(deps: Deps) -> StdResult<NumTokensResponse> {
+    // Subscriptions are never removed so the id counter is the number of tokens
+    let count = SUBSCRIPTION_ID.load(deps.storage)?;
+
+    Ok(NumTokensResponse { count })
+}
+
+pub fn query_contract_info() -> ContractInfoResponse {
+    ContractInfoResponse {
+        name: COLLECTION_NAME.to_string(),
+        symbol: COLLECTION_SYMBOL.to_string(),
+    }
+}
+
+pub fn query_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse> {
+    let subscription_id = parse_token_id(&token_id)?;
+    let subscription = SUBSCRIPTIONS.load(deps.storage, subscription_id)?;
+    let subscription_plan = SUBSCRIPTION_PLANS.load(deps.storage, subscription.plan_id)?;
+    let organization = ORGANIZATIONS.load(deps.storage, subscription_plan.organization_id)?;
+
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: SubscriptionMetadata {
+            plan_id: subscription.plan_id,
+            plan_name: subscription_plan.name,
+            organization_id: subscription_plan.organization_id,
+            organization_name: organization.name,
+            expiration: subscription.expiration,
+            canceled: subscription.canceled,
+        },
+    })
+}
+
+pub fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<AllNftInfoResponse> {
+    Ok(AllNftInfoResponse {
+        access: query_owner_of(deps, env, token_id.clone(), include_expired)?,
+        info: query_nft_info(deps, token_id)?,
+    })
+}
+
+pub fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|token_id| parse_token_id(&token_id))
+        .transpose()?
+        .map(Bound::exclusive);
+
+    let tokens = OWNER_TOKENS
+        .prefix(owner_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|subscription_id| subscription_id.to_string()))
+        .collect::<StdResult<Vec<String>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_all_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|token_id| parse_token_id(&token_id))
+        .transpose()?
+        .map(Bound::exclusive);
+
+    let tokens = SUBSCRIPTIONS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|subscription_id| subscription_id.to_string()))
+        .collect::<StdResult<Vec<String>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
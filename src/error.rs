@@ -20,4 +20,196 @@ pub enum ContractError {
 
     #[error("Subscription is already active")]
     AlreadySubscribed {},
+
+    #[error("Invalid payment")]
+    InvalidPayment {},
+
+    #[error("Invalid subscription plan prices")]
+    InvalidPrices {},
+
+    #[error("Insufficient revenue")]
+    InsufficientRevenue {},
+
+    #[error("Subscription cannot be renewed")]
+    NotRenewable {},
+
+    #[error("Price oracle is not configured")]
+    OracleNotConfigured {},
+
+    #[error("Oracle price is stale")]
+    StalePrice {},
+
+    #[error("Invalid oracle price")]
+    InvalidOraclePrice {},
+
+    #[error("Price exceeds the maximum price")]
+    SlippageExceeded {},
+
+    #[error("Subscription plan is not metered")]
+    NotMetered {},
+
+    #[error("Usage exceeds the cap of the period")]
+    UsageCapExceeded {},
+
+    #[error("Insufficient prepaid balance")]
+    InsufficientBalance {},
+
+    #[error("Invalid subscription plan kind")]
+    InvalidPlanKind {},
+
+    #[error("Not enough uses left on the access pass")]
+    InsufficientUses {},
+
+    #[error("No installment is left to pay")]
+    NoInstallmentDue {},
+
+    #[error("Invalid dunning policy")]
+    InvalidDunningPolicy {},
+
+    #[error("Subscription cannot be paused")]
+    NotPausable {},
+
+    #[error("Subscription is paused")]
+    SubscriptionPaused {},
+
+    #[error("Subscription is not paused")]
+    NotPaused {},
+
+    #[error("Hook is already registered")]
+    HookAlreadyExists {},
+
+    #[error("Hook is not registered")]
+    HookNotFound {},
+
+    #[error("Subscription cannot be transferred")]
+    NotTransferable {},
+
+    #[error("Approval has already expired")]
+    ApprovalExpired {},
+
+    #[error("Invalid coupon")]
+    InvalidCoupon {},
+
+    #[error("Coupon already exists")]
+    CouponAlreadyExists {},
+
+    #[error("Coupon is expired")]
+    CouponExpired {},
+
+    #[error("Coupon has no redemptions left")]
+    CouponExhausted {},
+
+    #[error("Coupon redemption limit reached for user")]
+    CouponLimitReached {},
+
+    #[error("Coupon is not applicable to the subscription plan")]
+    CouponNotApplicable {},
+
+    #[error("Name is empty")]
+    EmptyName {},
+
+    #[error("Name is too long")]
+    NameTooLong {},
+
+    #[error("Description is too long")]
+    DescriptionTooLong {},
+
+    #[error("Invalid website URL")]
+    InvalidUrl {},
+
+    #[error("Too many metadata entries")]
+    TooManyMetadataEntries {},
+
+    #[error("Metadata key or value is too long")]
+    MetadataTooLong {},
+
+    #[error("Too many features")]
+    TooManyFeatures {},
+
+    #[error("Invalid feature")]
+    InvalidFeature {},
+
+    #[error("Duration must be greater than zero")]
+    InvalidDuration {},
+
+    #[error("Price exceeds the maximum amount")]
+    PriceTooLarge {},
+
+    #[error("Invalid handle")]
+    InvalidHandle {},
+
+    #[error("Handle is already taken")]
+    HandleTaken {},
+
+    #[error("Handle is reserved by its previous organization")]
+    HandleReserved {},
+
+    #[error("Handle was changed too recently")]
+    HandleCooldown {},
+
+    #[error("Organization is already verified")]
+    AlreadyVerified {},
+
+    #[error("Organization is not verified")]
+    NotVerified {},
+
+    #[error("Subscription plan is full")]
+    PlanFull {},
+
+    #[error("Subscription plan has no waitlist")]
+    WaitlistDisabled {},
+
+    #[error("User is already on the waitlist")]
+    AlreadyWaitlisted {},
+
+    #[error("User is not on the waitlist")]
+    NotWaitlisted {},
+
+    #[error("Subscriber is not allowed to subscribe to this plan")]
+    NotAllowed {},
+
+    #[error("Invalid Merkle root")]
+    InvalidMerkleRoot {},
+
+    #[error("Invalid Merkle proof")]
+    InvalidProof {},
+
+    #[error("Subscriber does not have an active subscription to the prerequisite plan")]
+    PrerequisiteNotMet {},
+
+    #[error("A plan cannot be its own prerequisite")]
+    InvalidPrerequisite {},
+
+    #[error("Invalid token gate")]
+    InvalidTokenGate {},
+
+    #[error("Subscriber does not hold the tokens required by this plan")]
+    TokenGateNotMet {},
+
+    #[error("Invalid bundle")]
+    InvalidBundle {},
+
+    #[error("Bundle is not approved by every organization")]
+    BundleNotApproved {},
+
+    #[error("Subscription is managed by its bundle")]
+    BundledSubscription {},
+
+    #[error("Invalid revenue split")]
+    InvalidRevenueSplit {},
+
+    #[error("Invalid affiliate reward")]
+    InvalidAffiliateReward {},
+
+    #[error("Subscribers cannot refer themselves")]
+    InvalidReferrer {},
+
+    #[error("No referral rewards to claim")]
+    NoReferralRewards {},
+
+    #[error("Insufficient credit")]
+    InsufficientCredit {},
+
+    #[error("Subscription cannot change to this plan")]
+    InvalidPlanChange {},
 }
@@ -0,0 +1,514 @@
+use cosmwasm_std::{Addr, Env, Event, Timestamp};
+use cw_utils::Expiration;
+
+use crate::asset::Asset;
+use crate::state::{CreditEntry, CreditEntryKind, RevenueShare, Subscription};
+
+// Every subscription event starts with the same attributes so indexers can join them
+fn subscription_event(
+    ty: &str,
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> Event {
+    Event::new(ty)
+        .add_attribute("subscription_id", subscription_id.to_string())
+        .add_attribute("plan_id", subscription.plan_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("subscriber", &subscription.subscriber)
+        .add_attribute("timestamp", env.block.time.seconds().to_string())
+}
+
+// Amounts are split into the asset and the amount
+fn add_asset(event: Event, asset: &Asset) -> Event {
+    event
+        .add_attribute("asset", asset.info.to_string())
+        .add_attribute("amount", asset.amount.to_string())
+}
+
+fn seconds(timestamp: Timestamp) -> String {
+    timestamp.seconds().to_string()
+}
+
+pub fn config_updated(env: &Env, admin: &Addr) -> Event {
+    Event::new("config_updated")
+        .add_attribute("admin", admin)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn organization_created(env: &Env, organization_id: u32, owner: &Addr) -> Event {
+    Event::new("organization_created")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn organization_handle_updated(env: &Env, organization_id: u32, handle: Option<&str>) -> Event {
+    Event::new("organization_handle_updated")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("handle", handle.unwrap_or_default())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn verifiers_updated(env: &Env, verifiers: &[Addr]) -> Event {
+    let verifiers = verifiers
+        .iter()
+        .map(Addr::as_str)
+        .collect::<Vec<&str>>()
+        .join(",");
+    Event::new("verifiers_updated")
+        .add_attribute("verifiers", verifiers)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn organization_verified(env: &Env, organization_id: u32, verifier: &Addr) -> Event {
+    Event::new("organization_verified")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("verifier", verifier)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn verification_revoked(env: &Env, organization_id: u32, revoker: &Addr) -> Event {
+    Event::new("verification_revoked")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("revoker", revoker)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn plan_created(env: &Env, plan_id: u64, organization_id: u32) -> Event {
+    Event::new("plan_created")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+// The change is "joined", "left" or "admitted"
+pub fn waitlist_updated(
+    env: &Env,
+    organization_id: u32,
+    plan_id: u64,
+    user: &Addr,
+    change: &str,
+) -> Event {
+    Event::new("waitlist_updated")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("user", user)
+        .add_attribute("change", change)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn plan_access_updated(env: &Env, organization_id: u32, plan_id: u64) -> Event {
+    Event::new("plan_access_updated")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn token_gate_updated(env: &Env, organization_id: u32, plan_id: u64) -> Event {
+    Event::new("token_gate_updated")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn bundle_created(env: &Env, organization_id: u32, bundle_id: u64) -> Event {
+    Event::new("bundle_created")
+        .add_attribute("bundle_id", bundle_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn bundle_approved(env: &Env, organization_id: u32, bundle_id: u64) -> Event {
+    Event::new("bundle_approved")
+        .add_attribute("bundle_id", bundle_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn coupon_created(env: &Env, organization_id: u32, code_hash: &str) -> Event {
+    Event::new("coupon_created")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("code_hash", code_hash)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn usage_reporters_updated(env: &Env, organization_id: u32, reporters: &[Addr]) -> Event {
+    let reporters = reporters
+        .iter()
+        .map(Addr::as_str)
+        .collect::<Vec<&str>>()
+        .join(",");
+    Event::new("usage_reporters_updated")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("reporters", reporters)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn hooks_updated(env: &Env, organization_id: u32, contract: &Addr, change: &str) -> Event {
+    Event::new("hooks_updated")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("contract", contract)
+        .add_attribute("change", change)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+// A hook whose failure is ignored failed
+pub fn hook_failed(env: &Env, error: &str) -> Event {
+    Event::new("hook_failed")
+        .add_attribute("error", error)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn revenue_split_updated(env: &Env, organization_id: u32, shares: &[RevenueShare]) -> Event {
+    Event::new("revenue_split_updated")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute(
+            "shares",
+            shares
+                .iter()
+                .map(|share| format!("{}:{}", share.recipient, share.bps))
+                .collect::<Vec<String>>()
+                .join(","),
+        )
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn affiliate_reward_updated(env: &Env, organization_id: u32, plan_id: u64) -> Event {
+    Event::new("affiliate_reward_updated")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn referral_rewards_claimed(env: &Env, referrer: &Addr, reward: &Asset) -> Event {
+    let event = Event::new("referral_rewards_claimed")
+        .add_attribute("referrer", referrer)
+        .add_attribute("timestamp", seconds(env.block.time));
+    add_asset(event, reward)
+}
+
+// The reason tells what the credit was added or taken for
+pub fn credit_updated(env: &Env, user: &Addr, entry_id: u64, entry: &CreditEntry) -> Event {
+    let event = Event::new("credit_updated")
+        .add_attribute("user", user)
+        .add_attribute("entry_id", entry_id.to_string())
+        .add_attribute(
+            "kind",
+            match entry.kind {
+                CreditEntryKind::Credit => "credit",
+                CreditEntryKind::Debit => "debit",
+            },
+        )
+        .add_attribute("reason", &entry.reason)
+        .add_attribute("timestamp", seconds(env.block.time));
+    add_asset(event, &entry.amount)
+}
+
+pub fn revenue_withdrawn(
+    env: &Env,
+    organization_id: u32,
+    recipient: &Addr,
+    withdrawal: &Asset,
+) -> Event {
+    let event = Event::new("revenue_withdrawn")
+        .add_attribute("organization_id", organization_id.to_string())
+        .add_attribute("recipient", recipient)
+        .add_attribute("timestamp", seconds(env.block.time));
+    add_asset(event, withdrawal)
+}
+
+pub fn subscription_created(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> Event {
+    subscription_event(
+        "subscription_created",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("payer", &subscription.payer)
+    .add_attribute("expiration", seconds(subscription.expiration))
+}
+
+pub fn subscription_renewed(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> Event {
+    subscription_event(
+        "subscription_renewed",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("payer", &subscription.payer)
+    .add_attribute("expiration", seconds(subscription.expiration))
+}
+
+pub fn subscription_canceled(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> Event {
+    subscription_event(
+        "subscription_canceled",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+}
+
+pub fn subscription_transferred(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    previous_subscriber: &Addr,
+) -> Event {
+    subscription_event(
+        "subscription_transferred",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("previous_subscriber", previous_subscriber)
+}
+
+pub fn plan_changed(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    previous_plan_id: u64,
+) -> Event {
+    subscription_event(
+        "plan_changed",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("previous_plan_id", previous_plan_id.to_string())
+    .add_attribute("payer", &subscription.payer)
+    .add_attribute("expiration", seconds(subscription.expiration))
+}
+
+pub fn token_approved(
+    env: &Env,
+    subscription_id: u64,
+    owner: &Addr,
+    spender: &Addr,
+    expires: &Expiration,
+) -> Event {
+    Event::new("token_approved")
+        .add_attribute("token_id", subscription_id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("spender", spender)
+        .add_attribute("expires", expires.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn token_approval_revoked(
+    env: &Env,
+    subscription_id: u64,
+    owner: &Addr,
+    spender: &Addr,
+) -> Event {
+    Event::new("token_approval_revoked")
+        .add_attribute("token_id", subscription_id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("spender", spender)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn operator_approved(env: &Env, owner: &Addr, operator: &Addr, expires: &Expiration) -> Event {
+    Event::new("operator_approved")
+        .add_attribute("owner", owner)
+        .add_attribute("operator", operator)
+        .add_attribute("expires", expires.to_string())
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn operator_revoked(env: &Env, owner: &Addr, operator: &Addr) -> Event {
+    Event::new("operator_revoked")
+        .add_attribute("owner", owner)
+        .add_attribute("operator", operator)
+        .add_attribute("timestamp", seconds(env.block.time))
+}
+
+pub fn subscription_paused(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    remaining_seconds: u64,
+) -> Event {
+    subscription_event(
+        "subscription_paused",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("remaining_seconds", remaining_seconds.to_string())
+}
+
+pub fn subscription_resumed(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> Event {
+    subscription_event(
+        "subscription_resumed",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("expiration", seconds(subscription.expiration))
+}
+
+pub fn subscription_past_due(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    grace_end: Timestamp,
+) -> Event {
+    subscription_event(
+        "subscription_past_due",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("grace_end", seconds(grace_end))
+}
+
+pub fn renewal_failed(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    next_retry: Option<Timestamp>,
+) -> Event {
+    let event = subscription_event(
+        "renewal_failed",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("attempt", subscription.renewal_attempts.to_string());
+    match next_retry {
+        Some(next_retry) => event.add_attribute("next_retry", seconds(next_retry)),
+        None => event,
+    }
+}
+
+pub fn subscription_lapsed(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+) -> Event {
+    subscription_event(
+        "subscription_lapsed",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+}
+
+pub fn usage_reported(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    units: u64,
+) -> Event {
+    subscription_event(
+        "usage_reported",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("units", units.to_string())
+}
+
+// Funds sent by the payer, the purpose tells what they paid for
+pub fn payment_received(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    payer: &Addr,
+    payment: &Asset,
+    purpose: &str,
+) -> Event {
+    let event = subscription_event(
+        "payment_received",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("payer", payer)
+    .add_attribute("purpose", purpose);
+    add_asset(event, payment)
+}
+
+// Funds taken from the prepaid balance, the purpose tells what they paid for
+pub fn balance_charged(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    charge: &Asset,
+    purpose: &str,
+) -> Event {
+    let event = subscription_event(
+        "balance_charged",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("purpose", purpose);
+    add_asset(event, charge)
+}
+
+// Funds returned to the payer, the reason tells where they came from
+pub fn refund_issued(
+    env: &Env,
+    organization_id: u32,
+    subscription_id: u64,
+    subscription: &Subscription,
+    refund: &Asset,
+    reason: &str,
+) -> Event {
+    let event = subscription_event(
+        "refund_issued",
+        env,
+        organization_id,
+        subscription_id,
+        subscription,
+    )
+    .add_attribute("recipient", &subscription.payer)
+    .add_attribute("reason", reason);
+    add_asset(event, refund)
+}
@@ -0,0 +1,79 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, QuerierWrapper, StdError, StdResult};
+
+use crate::msg::QueryMsg;
+
+// Wrapper around the address of a deployed subscription hub for contracts that gate access on it
+#[cw_serde]
+pub struct SubscriptionHubContract(pub Addr);
+
+impl SubscriptionHubContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    // Checks if the user has an active subscription to the plan
+    pub fn is_subscribed(
+        &self,
+        querier: &QuerierWrapper,
+        user: impl Into<String>,
+        plan_id: u64,
+    ) -> StdResult<bool> {
+        querier.query_wasm_smart(
+            self.addr(),
+            &QueryMsg::IsSubscribed {
+                user_address: user.into(),
+                plan_id,
+            },
+        )
+    }
+
+    // Checks if the user has an active subscription to a plan of the organization with the feature
+    pub fn has_entitlement(
+        &self,
+        querier: &QuerierWrapper,
+        user: impl Into<String>,
+        organization_id: u32,
+        feature: impl Into<String>,
+    ) -> StdResult<bool> {
+        querier.query_wasm_smart(
+            self.addr(),
+            &QueryMsg::HasEntitlement {
+                user_address: user.into(),
+                organization_id,
+                feature: feature.into(),
+            },
+        )
+    }
+
+    // Fails unless the user has an active subscription to the plan
+    pub fn require_subscription(
+        &self,
+        querier: &QuerierWrapper,
+        user: impl Into<String>,
+        plan_id: u64,
+    ) -> StdResult<()> {
+        if !self.is_subscribed(querier, user, plan_id)? {
+            return Err(StdError::generic_err(
+                "User is not subscribed to the subscription plan",
+            ));
+        }
+        Ok(())
+    }
+
+    // Fails unless the user has the feature through an active subscription
+    pub fn require_entitlement(
+        &self,
+        querier: &QuerierWrapper,
+        user: impl Into<String>,
+        organization_id: u32,
+        feature: impl Into<String>,
+    ) -> StdResult<()> {
+        if !self.has_entitlement(querier, user, organization_id, feature)? {
+            return Err(StdError::generic_err(
+                "User does not have the required entitlement",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
+pub mod asset;
 pub mod contract;
+mod cw721;
 mod error;
+mod events;
+pub mod helpers;
 pub mod msg;
 pub mod state;
+mod validation;
 
 pub use crate::error::ContractError;
@@ -1,51 +1,370 @@
 use std::collections::BTreeMap;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, CosmosMsg, Decimal, StdResult, Timestamp, Uint128, WasmMsg,
+};
+use cw_utils::Expiration;
 
-use crate::state::{DurationUnit, Organization, Subscription, SubscriptionPlan};
+use crate::asset::{Asset, AssetInfo};
+use crate::state::{
+    AffiliateReward, Bundle, BundleComponent, BundleSubscription, Config, Coupon, CouponDuration,
+    CreditEntry, Discount, DunningPolicy, DurationUnit, Hook, HookFailurePolicy,
+    InstallmentSchedule, Organization, PlanAccess, PlanKind, ReferencePrice, RevenueShare,
+    Subscription, SubscriptionPlan, SubscriptionStatus, TokenGate, ValidationLimits, Verification,
+};
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub oracle: Option<String>,
+    pub oracle_max_staleness: Option<u64>,
+    pub limits: Option<ValidationLimits>,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
+    // Update the contract configuration
+    UpdateConfig {
+        admin: Option<String>,
+        oracle: Option<String>,
+        oracle_max_staleness: Option<u64>,
+        limits: Option<ValidationLimits>,
+    },
     // Create a new organization
     CreateOrganization {
         name: String,
         description: String,
+        handle: Option<String>,
         website: Option<String>,
         metadata: Option<BTreeMap<String, String>>,
     },
+    // Set the addresses allowed to verify organizations besides the admin
+    SetVerifiers {
+        verifiers: Vec<String>,
+    },
+    // Mark an organization as verified
+    VerifyOrganization {
+        organization_id: u32,
+        proof_url: Option<String>,
+    },
+    // Remove the verification of an organization
+    RevokeVerification {
+        organization_id: u32,
+    },
+    // Set or clear the handle of an organization
+    SetOrganizationHandle {
+        organization_id: u32,
+        handle: Option<String>,
+    },
     // Create a new subscription plan for an organization
     CreateSubscriptionPlan {
         organization_id: u32,
         name: String,
         description: String,
-        price: Uint128,
+        prices: Vec<Asset>,
+        reference_price: Option<ReferencePrice>,
+        kind: Option<PlanKind>,
+        dunning: Option<DunningPolicy>,
         duration: u8,
         duration_unit: DurationUnit,
         features: Option<Vec<String>>,
         metadata: Option<BTreeMap<String, String>>,
         cancelable: bool,
         refundable: bool,
+        transferable: bool,
+        pausable: bool,
+        max_pause_duration: Option<u64>,
+        max_subscribers: Option<u32>,
+        waitlist: bool,
     },
-    // Subscribe to a subscription plan
+    // Subscribe to a subscription plan, optionally on behalf of a beneficiary
     SubscribePlan {
         plan_id: u64,
+        beneficiary: Option<String>,
+        coupon: Option<String>,
+        max_price: Option<Uint128>,
+        // Hex encoded Merkle proof of the subscriber, required by Merkle allowlist plans
+        proof: Option<Vec<String>>,
+        // The address that referred the subscriber
+        referrer: Option<String>,
+    },
+    // Extend a subscription by one period, anyone can renew but the payer stays the same
+    RenewSubscription {
+        subscription_id: u64,
+        max_price: Option<Uint128>,
+    },
+    // Move a recurring subscription to another recurring plan of the organization, the unused time
+    // is refunded to the payer's credit and the new plan starts a new period paid by the sender
+    ChangePlan {
+        subscription_id: u64,
+        plan_id: u64,
+        max_price: Option<Uint128>,
+        // Hex encoded Merkle proof of the subscriber, required by Merkle allowlist plans
+        proof: Option<Vec<String>>,
+    },
+    // Add funds to the prepaid balance of a subscription
+    TopUp {
+        subscription_id: u64,
+    },
+    // Pay the next installment of an installment subscription
+    PayInstallment {
+        subscription_id: u64,
+    },
+    // Report the units used by a metered subscription and charge its prepaid balance
+    ReportUsage {
+        subscription_id: u64,
+        units: u64,
+    },
+    // Pause a subscription and freeze its remaining time
+    PauseSubscription {
+        subscription_id: u64,
+    },
+    // Resume a paused subscription with the remaining time it had when paused
+    ResumeSubscription {
+        subscription_id: u64,
+    },
+    // Close the elapsed usage periods of a subscription and charge their overage
+    ProcessSubscription {
+        subscription_id: u64,
+    },
+    // Set the addresses allowed to report usage for an organization
+    SetUsageReporters {
+        organization_id: u32,
+        reporters: Vec<String>,
+    },
+    // Register a contract notified of the lifecycle of an organization's subscriptions
+    AddHook {
+        organization_id: u32,
+        contract: String,
+        on_failure: HookFailurePolicy,
+    },
+    // Remove a registered hook contract
+    RemoveHook {
+        organization_id: u32,
+        contract: String,
+    },
+    // Subscribe to or renew a subscription plan by sending CW20 tokens
+    Receive(Cw20ReceiveMsg),
+    // Add the sent funds to the credit of the recipient, or the sender if not given
+    DepositCredit {
+        recipient: Option<String>,
+    },
+    // Withdraw credit of the sender, the whole credit of the asset if no amount is given
+    WithdrawCredit {
+        asset: AssetInfo,
+        amount: Option<Uint128>,
+    },
+    // Withdraw the collected revenue of an organization
+    WithdrawRevenue {
+        organization_id: u32,
+        asset: AssetInfo,
+        amount: Option<Uint128>,
+    },
+    // Share the incoming revenue of an organization with other recipients, an empty list removes the split
+    SetRevenueSplit {
+        organization_id: u32,
+        shares: Vec<RevenueShare>,
+    },
+    // Claim the accrued revenue share of the sender
+    ClaimRevenue {
+        organization_id: u32,
+        asset: AssetInfo,
+    },
+    // Queue for a full subscription plan
+    JoinWaitlist {
+        plan_id: u64,
+    },
+    // Leave the waitlist of a subscription plan
+    LeaveWaitlist {
+        plan_id: u64,
+    },
+    // Admit the next users of the waitlist up to the free seats, a seat is reserved for each
+    AdmitFromWaitlist {
+        plan_id: u64,
+        count: u32,
+    },
+    // Restrict who can subscribe to a subscription plan
+    SetPlanAccess {
+        plan_id: u64,
+        access: PlanAccess,
+    },
+    // Add and remove addresses on the allowlist of a subscription plan
+    UpdatePlanAllowlist {
+        plan_id: u64,
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    // Reward referrers of a plan's subscribers with a commission, None removes the reward
+    SetAffiliateReward {
+        plan_id: u64,
+        reward: Option<AffiliateReward>,
+    },
+    // Claim the referral rewards of the sender in every asset
+    ClaimReferralRewards {},
+    // Require subscribers of a plan to hold CW20 or CW721 tokens, None removes the gate
+    SetTokenGate {
+        plan_id: u64,
+        gate: Option<TokenGate>,
+    },
+    // Create a bundle of subscription plans sold together, the price is split by the shares
+    CreateBundle {
+        organization_id: u32,
+        name: String,
+        components: Vec<BundleComponent>,
+        prices: Vec<Asset>,
+        duration: u8,
+        duration_unit: DurationUnit,
+    },
+    // Approve a bundle that includes a plan of the organization
+    ApproveBundle {
+        bundle_id: u64,
+        organization_id: u32,
+    },
+    // Subscribe to every plan of a bundle, optionally on behalf of a beneficiary
+    SubscribeBundle {
+        bundle_id: u64,
+        beneficiary: Option<String>,
+    },
+    // Extend every subscription of a bundle by one period, anyone can renew but the payer stays
+    // the same
+    RenewBundle {
+        bundle_subscription_id: u64,
+    },
+    // Cancel every subscription of a bundle, the unused shares of refundable plans are refunded
+    CancelBundle {
+        bundle_subscription_id: u64,
     },
     // Cancel a subscription plan
     CancelPlan {
         plan_id: u64,
     },
+    // Transfer a subscription to another address, which also becomes its payer
+    TransferSubscription {
+        subscription_id: u64,
+        recipient: String,
+    },
+    // Create a coupon for an organization's subscription plans
+    CreateCoupon {
+        organization_id: u32,
+        code: String,
+        discount: Discount,
+        duration: CouponDuration,
+        max_redemptions: Option<u32>,
+        per_user_limit: Option<u32>,
+        expiration: Option<Timestamp>,
+        plan_ids: Option<Vec<u64>>,
+    },
+    // CW721: Transfer a subscription token to another address
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    // CW721: Transfer a subscription token to a contract and trigger an action
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    // CW721: Allow the spender to transfer the subscription token
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    // CW721: Remove a previously granted approval
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    // CW721: Allow the operator to transfer all of the sender's subscription tokens
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    // CW721: Remove a previously granted operator approval
+    RevokeAll {
+        operator: String,
+    },
+}
+
+// The message sent by a CW20 contract when tokens are sent to the hub
+#[cw_serde]
+pub struct Cw20ReceiveMsg {
+    pub sender: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+}
+
+// The messages that can be embedded in a CW20 send to the hub
+#[cw_serde]
+pub enum ReceiveMsg {
+    // Add the sent tokens to the credit of the recipient, or the sender if not given
+    DepositCredit {
+        recipient: Option<String>,
+    },
+    // Subscribe to a subscription plan, optionally on behalf of a beneficiary
+    SubscribePlan {
+        plan_id: u64,
+        beneficiary: Option<String>,
+        coupon: Option<String>,
+        max_price: Option<Uint128>,
+        // Hex encoded Merkle proof of the subscriber, required by Merkle allowlist plans
+        proof: Option<Vec<String>>,
+        // The address that referred the subscriber
+        referrer: Option<String>,
+    },
+    // Extend a subscription by one period
+    RenewSubscription {
+        subscription_id: u64,
+        max_price: Option<Uint128>,
+    },
+    // Move a recurring subscription to another recurring plan of the organization
+    ChangePlan {
+        subscription_id: u64,
+        plan_id: u64,
+        max_price: Option<Uint128>,
+        // Hex encoded Merkle proof of the subscriber, required by Merkle allowlist plans
+        proof: Option<Vec<String>>,
+    },
+    // Add funds to the prepaid balance of a subscription
+    TopUp {
+        subscription_id: u64,
+    },
+    // Pay the next installment of an installment subscription
+    PayInstallment {
+        subscription_id: u64,
+    },
+    // Subscribe to every plan of a bundle, optionally on behalf of a beneficiary
+    SubscribeBundle {
+        bundle_id: u64,
+        beneficiary: Option<String>,
+    },
+    // Extend every subscription of a bundle by one period, anyone can renew but the payer stays
+    // the same
+    RenewBundle {
+        bundle_subscription_id: u64,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    // Get the contract configuration
+    #[returns(Config)]
+    Config {},
     // Get the organization with the given ID
     #[returns(OrganizationResponse)]
     Organization { organization_id: u32 },
+    // Get the organization with the given handle
+    #[returns(OrganizationResponse)]
+    OrganizationByHandle { handle: String },
+    // Get all organizations, optionally only the verified or unverified ones
+    #[returns(Vec<OrganizationResponse>)]
+    Organizations {
+        verified: Option<bool>,
+        start_after: Option<u32>,
+        limit: Option<u8>,
+    },
     // Get all organizations owned by the given user
     #[returns(Vec<OrganizationResponse>)]
     UserOrganizations { user_address: String },
@@ -58,6 +377,41 @@ pub enum QueryMsg {
     // Get the subscription with the given ID
     #[returns(SubscriptionResponse)]
     Subscription { subscription_id: u64 },
+    // Get the subscriber limit and the number of active subscribers of the given plan
+    #[returns(PlanCapacityResponse)]
+    PlanCapacity { plan_id: u64 },
+    // Get the queued users of the given plan in order
+    #[returns(Vec<WaitlistEntry>)]
+    Waitlist {
+        plan_id: u64,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    // Get the access control of the given plan
+    #[returns(PlanAccess)]
+    PlanAccess { plan_id: u64 },
+    // Get the bundle with the given id
+    #[returns(Bundle)]
+    Bundle { bundle_id: u64 },
+    // Get the bundle subscription with the given id
+    #[returns(BundleSubscription)]
+    BundleSubscription { bundle_subscription_id: u64 },
+    // Get the affiliate reward of the given plan
+    #[returns(Option<AffiliateReward>)]
+    AffiliateReward { plan_id: u64 },
+    // Get the referrals and the earned and claimable commission of the given referrer
+    #[returns(ReferrerStatsResponse)]
+    ReferrerStats { referrer: String },
+    // Get the token gate of the given plan
+    #[returns(Option<TokenGate>)]
+    TokenGate { plan_id: u64 },
+    // Get the allowlisted addresses of the given plan
+    #[returns(Vec<Addr>)]
+    PlanAllowlist {
+        plan_id: u64,
+        start_after: Option<String>,
+        limit: Option<u8>,
+    },
     // Get all subscriptions owned by the given user
     #[returns(Vec<SubscriptionResponse>)]
     UserSubscriptions {
@@ -75,12 +429,187 @@ pub enum QueryMsg {
     // Checks if the given user is subscribed to the given subscription plan
     #[returns(bool)]
     IsSubscribed { user_address: String, plan_id: u64 },
+    // Checks if the given user has an active subscription to a plan of the given organization with the given feature
+    #[returns(bool)]
+    HasEntitlement {
+        user_address: String,
+        organization_id: u32,
+        feature: String,
+    },
+    // Get the collected revenue of the given organization for each asset
+    #[returns(Vec<Asset>)]
+    OrganizationRevenue { organization_id: u32 },
+    // Get the credit balance of the given user for each asset
+    #[returns(Vec<Asset>)]
+    Credits { user_address: String },
+    // Get the credit and debit entries of the given user
+    #[returns(Vec<CreditEntryResponse>)]
+    CreditLedger {
+        user_address: String,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    // Get the revenue split of the given organization
+    #[returns(Vec<RevenueShare>)]
+    RevenueSplit { organization_id: u32 },
+    // Get the claimable revenue share of every recipient of the given organization
+    #[returns(Vec<RecipientRevenueResponse>)]
+    RecipientRevenue { organization_id: u32 },
+    // Get the payments of a refundable subscription that are held until they are earned
+    #[returns(Vec<Asset>)]
+    Escrow { subscription_id: u64 },
+    // Get the prepaid balance and the usage per period of a metered subscription
+    #[returns(UsageResponse)]
+    Usage {
+        subscription_id: u64,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    // Get the payment progress of an installment subscription
+    #[returns(InstallmentsResponse)]
+    Installments { subscription_id: u64 },
+    // Get the addresses allowed to report usage for the given organization
+    #[returns(Vec<Addr>)]
+    UsageReporters { organization_id: u32 },
+    // Get the addresses allowed to verify organizations besides the admin
+    #[returns(Vec<Addr>)]
+    Verifiers {},
+    // Get the hook contracts of the given organization
+    #[returns(Vec<Hook>)]
+    Hooks { organization_id: u32 },
+    // Get the coupon of the given organization with the given code
+    #[returns(CouponResponse)]
+    CouponInfo { organization_id: u32, code: String },
+    // CW721: Get the owner of the given subscription token
+    #[returns(OwnerOfResponse)]
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // CW721: Get the approval of the spender for the given subscription token
+    #[returns(ApprovalResponse)]
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    // CW721: Get all approvals for the given subscription token
+    #[returns(ApprovalsResponse)]
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // CW721: Get all operators approved by the given owner
+    #[returns(OperatorsResponse)]
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // CW721: Get the total number of subscription tokens
+    #[returns(NumTokensResponse)]
+    NumTokens {},
+    // CW721: Get the collection information
+    #[returns(ContractInfoResponse)]
+    ContractInfo {},
+    // CW721: Get the metadata of the given subscription token
+    #[returns(NftInfoResponse)]
+    NftInfo { token_id: String },
+    // CW721: Get the owner and metadata of the given subscription token
+    #[returns(AllNftInfoResponse)]
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // CW721: Get the subscription tokens owned by the given owner
+    #[returns(TokensResponse)]
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // CW721: Get all subscription tokens
+    #[returns(TokensResponse)]
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+// The query interface the price oracle has to implement
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum OracleQueryMsg {
+    // Get the price of a single unit of the asset in the given currency
+    #[returns(OraclePriceResponse)]
+    Price { asset: AssetInfo, currency: String },
+}
+
+// The CW20 queries used to check token gates
+#[cw_serde]
+pub enum Cw20QueryMsg {
+    Balance { address: String },
+}
+
+#[cw_serde]
+pub struct Cw20BalanceResponse {
+    pub balance: Uint128,
+}
+
+// The CW721 queries used to check token gates
+#[cw_serde]
+pub enum Cw721QueryMsg {
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct OraclePriceResponse {
+    pub price: Decimal,
+    pub updated_at: Timestamp,
 }
 
 #[cw_serde]
 pub struct OrganizationResponse {
     pub id: u32,
     pub data: Organization,
+    pub verified: bool,
+    pub verification: Option<Verification>,
+}
+
+#[cw_serde]
+pub struct CreditEntryResponse {
+    pub id: u64,
+    pub data: CreditEntry,
+}
+
+#[cw_serde]
+pub struct ReferrerStatsResponse {
+    pub referrals: u32,
+    pub earned: Vec<Asset>,
+    pub claimable: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct RecipientRevenueResponse {
+    pub recipient: Addr,
+    pub claimable: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct PlanCapacityResponse {
+    pub max_subscribers: Option<u32>,
+    pub active_subscribers: u32,
+}
+
+#[cw_serde]
+pub struct WaitlistEntry {
+    pub position: u64,
+    pub user: Addr,
 }
 
 #[cw_serde]
@@ -94,3 +623,201 @@ pub struct SubscriptionResponse {
     pub id: u64,
     pub data: Subscription,
 }
+
+#[cw_serde]
+pub struct CouponResponse {
+    pub organization_id: u32,
+    pub code_hash: String,
+    // Number of redemptions left, None if unlimited
+    pub remaining_redemptions: Option<u32>,
+    pub data: Coupon,
+}
+
+#[cw_serde]
+pub struct UsageResponse {
+    pub subscription_id: u64,
+    // Index of the current usage period
+    pub current_period: u64,
+    pub balance: Option<Asset>,
+    pub periods: Vec<UsagePeriod>,
+}
+
+#[cw_serde]
+pub struct InstallmentsResponse {
+    pub subscription_id: u64,
+    pub status: SubscriptionStatus,
+    pub schedule: InstallmentSchedule,
+    pub paid: Asset,
+    // Amount of the installments left to pay
+    pub outstanding: Asset,
+    pub next_installment: Option<Asset>,
+    pub next_due: Option<Timestamp>,
+    // Whether the next installment is late beyond the grace period
+    pub overdue: bool,
+}
+
+#[cw_serde]
+pub struct UsagePeriod {
+    pub period: u64,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub units: u64,
+    pub cost: Uint128,
+    pub unpaid: Uint128,
+    pub settled: bool,
+}
+
+#[cw_serde]
+pub struct Cw721Approval {
+    pub spender: String,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct OwnerOfResponse {
+    pub owner: String,
+    pub approvals: Vec<Cw721Approval>,
+}
+
+#[cw_serde]
+pub struct ApprovalResponse {
+    pub approval: Cw721Approval,
+}
+
+#[cw_serde]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Cw721Approval>,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<Cw721Approval>,
+}
+
+#[cw_serde]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+}
+
+#[cw_serde]
+pub struct SubscriptionMetadata {
+    pub plan_id: u64,
+    pub plan_name: String,
+    pub organization_id: u32,
+    pub organization_name: String,
+    pub expiration: Timestamp,
+    pub canceled: bool,
+}
+
+#[cw_serde]
+pub struct NftInfoResponse {
+    pub token_uri: Option<String>,
+    pub extension: SubscriptionMetadata,
+}
+
+#[cw_serde]
+pub struct AllNftInfoResponse {
+    pub access: OwnerOfResponse,
+    pub info: NftInfoResponse,
+}
+
+#[cw_serde]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
+
+// The message sent to the hook contracts of an organization
+#[cw_serde]
+pub enum SubscriptionHookMsg {
+    // A subscription to one of the organization's plans was created
+    Created {
+        subscription_id: u64,
+        plan_id: u64,
+        subscriber: Addr,
+        expiration: Timestamp,
+    },
+    // A subscription was extended by another period
+    Renewed {
+        subscription_id: u64,
+        plan_id: u64,
+        subscriber: Addr,
+        expiration: Timestamp,
+    },
+    // A subscription was canceled by its subscriber or payer
+    Canceled {
+        subscription_id: u64,
+        plan_id: u64,
+        subscriber: Addr,
+    },
+    // A subscription ran out without being renewed
+    Expired {
+        subscription_id: u64,
+        plan_id: u64,
+        subscriber: Addr,
+    },
+    // A subscription moved to another plan of the organization
+    PlanChanged {
+        subscription_id: u64,
+        old_plan_id: u64,
+        new_plan_id: u64,
+        subscriber: Addr,
+    },
+}
+
+impl SubscriptionHookMsg {
+    // Serializes the message wrapped in the hook's SubscriptionHook variant
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&HookExecuteMsg::SubscriptionHook(self))
+    }
+
+    // Creates a message that executes the hook contract
+    pub fn into_cosmos_msg(self, contract_addr: String) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr,
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[cw_serde]
+enum HookExecuteMsg {
+    SubscriptionHook(SubscriptionHookMsg),
+}
+
+// The message sent to the receiving contract of a SendNft
+#[cw_serde]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+impl Cw721ReceiveMsg {
+    // Serializes the message wrapped in the receiver's ReceiveNft variant
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&ReceiverExecuteMsg::ReceiveNft(self))
+    }
+
+    // Creates a message that executes the receiving contract
+    pub fn into_cosmos_msg(self, contract_addr: String) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr,
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[cw_serde]
+enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
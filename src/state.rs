@@ -1,13 +1,59 @@
 use std::collections::BTreeMap;
+use std::fmt;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+use crate::asset::{Asset, AssetInfo};
+use crate::error::ContractError;
 
 #[cw_serde]
 pub struct Config {
     // The address of the contract admin
     pub admin: Addr,
+    // The address of the price oracle used by plans priced in a reference currency
+    pub oracle: Option<Addr>,
+    // Maximum age of an oracle price in seconds
+    pub oracle_max_staleness: u64,
+    // Limits on the organizations and subscription plans that can be created
+    pub limits: ValidationLimits,
+}
+
+#[cw_serde]
+pub struct ValidationLimits {
+    // Maximum length of a name
+    pub max_name_length: u32,
+    // Maximum length of a description
+    pub max_description_length: u32,
+    // Maximum length of a website URL
+    pub max_url_length: u32,
+    // Maximum number of metadata entries
+    pub max_metadata_entries: u32,
+    // Maximum length of a metadata key
+    pub max_metadata_key_length: u32,
+    // Maximum length of a metadata value
+    pub max_metadata_value_length: u32,
+    // Maximum number of features of a subscription plan
+    pub max_features: u32,
+    // Maximum amount of a price, unlimited if not set
+    pub max_price: Option<Uint128>,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        ValidationLimits {
+            max_name_length: 64,
+            max_description_length: 1024,
+            max_url_length: 256,
+            max_metadata_entries: 16,
+            max_metadata_key_length: 64,
+            max_metadata_value_length: 256,
+            max_features: 32,
+            max_price: None,
+        }
+    }
 }
 
 // The config key is used to store the contract's configuration
@@ -25,6 +71,10 @@ pub struct Organization {
     pub website: Option<String>,
     // Key-Value metadata of the organization
     pub metadata: Option<BTreeMap<String, String>>,
+    // Unique lowercase handle of the organization
+    pub handle: Option<String>,
+    // Time the handle was last changed
+    pub handle_updated_at: Option<Timestamp>,
 }
 
 // The organization_id is the primary key for the organization
@@ -33,6 +83,28 @@ pub const ORGANIZATION_ID: Item<u32> = Item::new("organization_id");
 // The organizations map stores the organization_id -> organization
 pub const ORGANIZATIONS: Map<u32, Organization> = Map::new("organizations");
 
+// The organization_handles map stores the handle -> organization_id
+pub const ORGANIZATION_HANDLES: Map<&str, u32> = Map::new("organization_handles");
+
+// The released_handles map stores the handle -> (previous organization_id, release time)
+pub const RELEASED_HANDLES: Map<&str, (u32, Timestamp)> = Map::new("released_handles");
+
+#[cw_serde]
+pub struct Verification {
+    // The address that verified the organization
+    pub verifier: Addr,
+    // Time the organization was verified
+    pub verified_at: Timestamp,
+    // Link to the proof of the organization's identity
+    pub proof_url: Option<String>,
+}
+
+// The verifiers item stores the addresses allowed to verify organizations besides the admin
+pub const VERIFIERS: Item<Vec<Addr>> = Item::new("verifiers");
+
+// The verifications map stores the organization_id -> verification
+pub const VERIFICATIONS: Map<u32, Verification> = Map::new("verifications");
+
 // The user_organizations map stores the user -> organization_id
 pub const USER_ORGANIZATIONS: Map<Addr, Vec<u32>> = Map::new("user_organizations");
 
@@ -44,6 +116,94 @@ pub enum DurationUnit {
     Year,
 }
 
+#[cw_serde]
+pub enum PlanAccess {
+    // Anyone can subscribe
+    Public,
+    // Only addresses on the allowlist managed by the organization can subscribe
+    Allowlist,
+    // Only addresses included in the Merkle tree can subscribe, they prove it with a Merkle proof
+    // The leaves are the sha256 hashes of the addresses, the root is hex encoded
+    MerkleAllowlist { root: String },
+    // Only holders of an active subscription to the given plan can subscribe
+    Prerequisite { plan_id: u64 },
+}
+
+#[cw_serde]
+pub enum TokenRequirement {
+    // Holding at least the given balance of a CW20 token
+    Cw20 { address: Addr, min_balance: Uint128 },
+    // Owning at least one token of a CW721 collection
+    Cw721 { address: Addr },
+}
+
+#[cw_serde]
+pub struct TokenGate {
+    // Tokens the subscriber has to hold when subscribing
+    pub requirement: TokenRequirement,
+    // Percentage off the first period for holders, applied after coupons
+    pub holder_discount: Option<u8>,
+}
+
+#[cw_serde]
+pub struct AffiliateReward {
+    // Commission of the referrer in basis points, paid from the organization's revenue
+    pub bps: u16,
+    // Whether the commission applies to every payment or only the first one
+    pub recurring: bool,
+}
+
+#[cw_serde]
+pub enum PlanKind {
+    // Time based plan paid upfront for every period
+    Recurring,
+    // Pay-per-use plan drawn down from a prepaid balance, the prices are per unit rates
+    Metered {
+        // Maximum number of units that can be used per period
+        usage_cap: Option<u64>,
+    },
+    // Time based plan that includes a usage quota per period, the prices are per period
+    Hybrid {
+        // Number of units included in every period
+        included_units: u64,
+        // Per unit rates of the usage above the included units, charged from a prepaid balance
+        overage_prices: Vec<Asset>,
+    },
+    // One-time purchase that never expires
+    Lifetime,
+    // One-time purchase of a fixed number of uses that never expire
+    AccessPass {
+        // Number of uses included in the pass
+        uses: u32,
+    },
+    // Plan for the whole duration paid in installments spread evenly over the duration
+    Installments {
+        // Number of installments
+        count: u32,
+        // Seconds an installment can be late before the subscription is suspended
+        grace_period: u64,
+    },
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct DunningPolicy {
+    // Seconds access continues after a renewal could not be paid
+    pub grace_period: u64,
+    // Seconds after the expiration at which the renewal is retried
+    pub retry_schedule: Vec<u64>,
+}
+
+#[cw_serde]
+pub struct ReferencePrice {
+    // Reference currency of the price, e.g. USD
+    pub currency: String,
+    // Price of the subscription plan in the reference currency
+    pub amount: Decimal,
+    // Assets accepted as payment, converted from the reference price by the oracle
+    pub assets: Vec<AssetInfo>,
+}
+
 #[cw_serde]
 pub struct SubscriptionPlan {
     // The address of the organization that owns the subscription plan
@@ -52,8 +212,14 @@ pub struct SubscriptionPlan {
     pub name: String,
     // Description of the subscription plan
     pub description: String,
-    // Price of the subscription plan
-    pub price: Uint128,
+    // Accepted prices of the subscription plan, empty if the plan is free
+    pub prices: Vec<Asset>,
+    // Price of the subscription plan in a reference currency, replaces the prices
+    pub reference_price: Option<ReferencePrice>,
+    // Billing model of the subscription plan
+    pub kind: PlanKind,
+    // How failed renewals of the subscription plan are retried
+    pub dunning: Option<DunningPolicy>,
     // Duration of the subscription plan
     pub duration: u8,
     // Unit of the duration of the subscription plan
@@ -66,6 +232,16 @@ pub struct SubscriptionPlan {
     pub cancelable: bool,
     // Whether the subscription plan is refundable
     pub refundable: bool,
+    // Whether subscriptions to the plan can be transferred to another address
+    pub transferable: bool,
+    // Whether subscribers can pause their subscription
+    pub pausable: bool,
+    // Seconds after which a paused subscription resumes, None if it can stay paused
+    pub max_pause_duration: Option<u64>,
+    // Maximum number of active subscribers, unlimited if not set
+    pub max_subscribers: Option<u32>,
+    // Whether users can queue for the plan while it is full
+    pub waitlist: bool,
 }
 
 // The subscription_plan_id is the primary key for the subscription plan
@@ -78,18 +254,247 @@ pub const SUBSCRIPTION_PLANS: Map<u64, SubscriptionPlan> = Map::new("subscriptio
 pub const ORGANIZATION_SUBSCRIPTION_PLANS: Map<u32, Vec<u64>> =
     Map::new("organization_subscription_plans");
 
+// The organization_revenue map stores the (organization_id, asset key) -> collected revenue
+pub const ORGANIZATION_REVENUE: Map<(u32, String), Asset> = Map::new("organization_revenue");
+
+#[cw_serde]
+pub enum CreditEntryKind {
+    // Funds added to the credit
+    Credit,
+    // Funds taken from the credit
+    Debit,
+}
+
+#[cw_serde]
+pub struct CreditEntry {
+    // Whether the entry added or took funds
+    pub kind: CreditEntryKind,
+    // Amount of the entry
+    pub amount: Asset,
+    // What the entry is for, "deposit", "withdrawal", "subscription" or "renewal"
+    pub reason: String,
+    // Subscription the credit was spent on
+    pub subscription_id: Option<u64>,
+    // When the entry was made
+    pub timestamp: Timestamp,
+}
+
+// The credits map stores the (user, asset key) -> credit balance of the user
+pub const CREDITS: Map<(Addr, String), Asset> = Map::new("credits");
+
+// The credit_ledger map stores the (user, entry id) -> credit and debit entries of the user
+pub const CREDIT_LEDGER: Map<(Addr, u64), CreditEntry> = Map::new("credit_ledger");
+
+// The credit_ledger_id map stores the user -> id of the last ledger entry of the user
+pub const CREDIT_LEDGER_ID: Map<Addr, u64> = Map::new("credit_ledger_id");
+
+#[cw_serde]
+pub struct RevenueShare {
+    // The address that receives the share
+    pub recipient: Addr,
+    // Share of the incoming revenue in basis points, the organization owner keeps the rest
+    pub bps: u16,
+}
+
+// The revenue_splits map stores the organization_id -> recipients of the incoming revenue
+pub const REVENUE_SPLITS: Map<u32, Vec<RevenueShare>> = Map::new("revenue_splits");
+
+// The recipient_revenue map stores the (organization_id, recipient, asset key) -> accrued share
+pub const RECIPIENT_REVENUE: Map<(u32, Addr, String), Asset> = Map::new("recipient_revenue");
+
+#[cw_serde]
+pub struct Escrow {
+    // The held part of the payments
+    pub amount: Asset,
+    // The part of the held amount owed to the referrer once it is released
+    pub commission: Uint128,
+}
+
+// The escrows map stores the (subscription_id, asset key) -> payments of a refundable
+// subscription that are held until they are earned
+pub const ESCROWS: Map<(u64, String), Escrow> = Map::new("escrows");
+
+#[cw_serde]
+pub struct BundleComponent {
+    // Subscription plan included in the bundle
+    pub plan_id: u64,
+    // Percentage of the bundle price paid to the organization of the plan
+    pub share: u8,
+}
+
+#[cw_serde]
+pub struct Bundle {
+    // Organization that created the bundle
+    pub organization_id: u32,
+    // Bundle name
+    pub name: String,
+    // Subscription plans included in the bundle
+    pub components: Vec<BundleComponent>,
+    // Price of the whole bundle for every period, in any of the given assets
+    pub prices: Vec<Asset>,
+    // Bundle duration
+    pub duration: u8,
+    // Bundle duration unit
+    pub duration_unit: DurationUnit,
+    // Organizations of the components that still have to approve the bundle before it is sold
+    pub pending_approvals: Vec<u32>,
+}
+
+// The bundle_id is the primary key for the bundle
+pub const BUNDLE_ID: Item<u64> = Item::new("bundle_id");
+
+// The bundles map stores the bundle_id -> bundle
+pub const BUNDLES: Map<u64, Bundle> = Map::new("bundles");
+
+#[cw_serde]
+pub struct BundleSubscription {
+    // Bundle id
+    pub bundle_id: u64,
+    // The address of the subscriber that is entitled to the subscriptions
+    pub subscriber: Addr,
+    // The address that paid for the bundle
+    pub payer: Addr,
+    // Linked subscriptions in the order of the bundle components
+    pub subscription_ids: Vec<u64>,
+    // Expiration of the linked subscriptions
+    pub expiration: Timestamp,
+    // Whether the bundle subscription is canceled
+    pub canceled: bool,
+}
+
+// The bundle_subscription_id is the primary key for the bundle subscription
+pub const BUNDLE_SUBSCRIPTION_ID: Item<u64> = Item::new("bundle_subscription_id");
+
+// The bundle_subscriptions map stores the bundle_subscription_id -> bundle_subscription
+pub const BUNDLE_SUBSCRIPTIONS: Map<u64, BundleSubscription> = Map::new("bundle_subscriptions");
+
+// The bundled_subscriptions map stores the subscription_id -> bundle_subscription_id it is linked to
+pub const BUNDLED_SUBSCRIPTIONS: Map<u64, u64> = Map::new("bundled_subscriptions");
+
 #[cw_serde]
 pub struct Subscription {
-    // The address of the subscriber
+    // The address of the subscriber that is entitled to the subscription
     pub subscriber: Addr,
+    // The address that paid for the subscription and receives any refunds
+    pub payer: Addr,
     // Subscription plan id
     pub plan_id: u64,
+    // When the subscription started, usage periods are counted from it
+    pub started: Timestamp,
     // Subscription expiration
     pub expiration: Timestamp,
     // Whether the subscription is canceled
     pub canceled: bool,
+    // Billing status of the subscription
+    pub status: SubscriptionStatus,
+    // Seconds access continues after the expiration while the renewal is past due
+    pub grace_period: u64,
+    // Number of failed renewal attempts since the expiration
+    pub renewal_attempts: u32,
+    // When the subscription was paused, the remaining time is frozen until it resumes
+    pub paused_at: Option<Timestamp>,
+    // Price paid for the subscription after discounts, None if the plan is free
+    pub price: Option<Asset>,
+    // Coupon applied to the subscription
+    pub coupon: Option<AppliedCoupon>,
+    // Prepaid balance of the subscription
+    pub balance: Option<Asset>,
+    // Uses left on access passes
+    pub remaining_uses: Option<u32>,
+    // Payment schedule of installment plans
+    pub installments: Option<InstallmentSchedule>,
+    // The address that referred the subscriber and earns the affiliate reward of the plan
+    pub referrer: Option<Addr>,
+}
+
+#[cw_serde]
+pub enum SubscriptionStatus {
+    // The subscription is in good standing
+    Active,
+    // Access is withheld until the missed installment is paid
+    Suspended,
+    // The renewal could not be paid, access continues during the grace period
+    PastDue,
+    // The subscription was not renewed within the grace period
+    Lapsed,
+    // The subscriber paused the subscription
+    Paused,
+}
+
+impl fmt::Display for SubscriptionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubscriptionStatus::Active => write!(f, "active"),
+            SubscriptionStatus::Suspended => write!(f, "suspended"),
+            SubscriptionStatus::PastDue => write!(f, "past_due"),
+            SubscriptionStatus::Lapsed => write!(f, "lapsed"),
+            SubscriptionStatus::Paused => write!(f, "paused"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct InstallmentSchedule {
+    // Total price of the subscription
+    pub total: Asset,
+    // Number of installments
+    pub count: u32,
+    // Number of installments paid so far
+    pub paid_installments: u32,
+    // Seconds between the due dates of the installments
+    pub interval: u64,
+    // Seconds an installment can be late before the subscription is suspended
+    pub grace_period: u64,
+}
+
+impl InstallmentSchedule {
+    // Amount of the given installment, the total is split as evenly as possible
+    fn installment_amount(&self, index: u32) -> Uint128 {
+        self.total.amount.multiply_ratio(index + 1, self.count)
+            - self.total.amount.multiply_ratio(index, self.count)
+    }
+
+    // The next installment to pay, None once every installment is paid
+    pub fn next_installment(&self) -> Option<Asset> {
+        (self.paid_installments < self.count).then(|| Asset {
+            info: self.total.info.clone(),
+            amount: self.installment_amount(self.paid_installments),
+        })
+    }
+
+    // Due date of the next installment, counted from the start of the subscription
+    pub fn next_due(&self, started: Timestamp) -> Option<Timestamp> {
+        (self.paid_installments < self.count)
+            .then(|| started.plus_seconds(self.interval * self.paid_installments as u64))
+    }
+
+    // Amount paid so far
+    pub fn paid(&self) -> Uint128 {
+        self.total
+            .amount
+            .multiply_ratio(self.paid_installments, self.count)
+    }
+
+    // Whether the next installment is late beyond the grace period
+    pub fn is_overdue(&self, started: Timestamp, now: Timestamp) -> bool {
+        self.next_due(started)
+            .is_some_and(|due| due.plus_seconds(self.grace_period) < now)
+    }
 }
 
+#[cw_serde]
+pub struct AppliedCoupon {
+    // Hash of the redeemed coupon code
+    pub code_hash: String,
+    // Discount of the coupon
+    pub discount: Discount,
+    // Number of periods the discount still applies to, None if forever
+    pub remaining_periods: Option<u32>,
+}
+
+// Expiration of subscriptions that do not expire over time
+pub const NEVER_EXPIRES: Timestamp = Timestamp::from_nanos(u64::MAX);
+
 // The subscription_id is the primary key for the subscription
 pub const SUBSCRIPTION_ID: Item<u64> = Item::new("subscription_id");
 
@@ -102,6 +507,160 @@ pub const SUBSCRIPTIONS: Map<u64, Subscription> = Map::new("subscriptions");
 // The user_subscriptions map stores the (subscriber, plan_id) -> subscription_id
 pub const USER_SUBSCRIPTIONS: Map<(Addr, u64), u64> = Map::new("user_subscriptions");
 
+// The owner_tokens map stores the (subscriber, subscription_id) of every subscription token a subscriber owns
+pub const OWNER_TOKENS: Map<(Addr, u64), bool> = Map::new("owner_tokens");
+
 // The subscription_plan_subscriptions map stores the subscription_plan_id and subscriber -> subscription_id
 pub const SUBSCRIPTION_PLAN_SUBSCRIPTIONS: Map<(u64, Addr), u64> =
     Map::new("subscription_plan_subscriptions");
+
+#[cw_serde]
+pub struct PeriodUsage {
+    // Number of units used in the period
+    pub units: u64,
+    // Amount drawn from the prepaid balance for the period
+    pub cost: Uint128,
+    // Overage of the period the prepaid balance could not cover when it was settled
+    pub unpaid: Uint128,
+    // Whether the usage of the period was charged
+    pub settled: bool,
+}
+
+// The usage map stores the (subscription_id, period) -> usage of the period
+pub const USAGE: Map<(u64, u64), PeriodUsage> = Map::new("usage");
+
+// The usage_reporters map stores the organization_id -> addresses allowed to report usage
+pub const USAGE_REPORTERS: Map<u32, Vec<Addr>> = Map::new("usage_reporters");
+
+#[cw_serde]
+pub enum HookFailurePolicy {
+    // A failing hook reverts the whole transaction
+    Revert,
+    // A failing hook is logged and the transaction goes through
+    Ignore,
+}
+
+#[cw_serde]
+pub struct Hook {
+    // Contract notified of the subscription lifecycle
+    pub contract: Addr,
+    // What happens when the hook contract fails
+    pub on_failure: HookFailurePolicy,
+}
+
+// The hooks map stores the organization_id -> contracts notified of subscription changes
+pub const HOOKS: Map<u32, Vec<Hook>> = Map::new("hooks");
+
+// The active_subscribers map stores the subscription_plan_id -> number of subscriptions holding a seat
+pub const ACTIVE_SUBSCRIBERS: Map<u64, u32> = Map::new("active_subscribers");
+
+// The seats map stores the subscription_id -> subscription_plan_id of subscriptions holding a seat
+pub const SEATS: Map<u64, u64> = Map::new("seats");
+
+// The waitlist map stores the (subscription_plan_id, position) -> queued user
+pub const WAITLIST: Map<(u64, u64), Addr> = Map::new("waitlist");
+
+// The waitlist_positions map stores the (subscription_plan_id, user) -> position in the waitlist
+pub const WAITLIST_POSITIONS: Map<(u64, Addr), u64> = Map::new("waitlist_positions");
+
+// The waitlist_next_position map stores the subscription_plan_id -> next position in the waitlist
+pub const WAITLIST_NEXT_POSITION: Map<u64, u64> = Map::new("waitlist_next_position");
+
+// The waitlist_admissions map stores the (subscription_plan_id, user) admitted from the waitlist holding a reserved seat
+pub const WAITLIST_ADMISSIONS: Map<(u64, Addr), bool> = Map::new("waitlist_admissions");
+
+// The plan_access map stores the subscription_plan_id -> access control of non public plans
+pub const PLAN_ACCESS: Map<u64, PlanAccess> = Map::new("plan_access");
+
+// The plan_allowlist map stores the (subscription_plan_id, user) allowed to subscribe
+pub const PLAN_ALLOWLIST: Map<(u64, Addr), bool> = Map::new("plan_allowlist");
+
+// The affiliate_rewards map stores the subscription_plan_id -> affiliate reward of referred subscriptions
+pub const AFFILIATE_REWARDS: Map<u64, AffiliateReward> = Map::new("affiliate_rewards");
+
+// The referrals map stores the referrer -> number of referred subscriptions that earned a commission
+pub const REFERRALS: Map<Addr, u32> = Map::new("referrals");
+
+// The referral_earnings map stores the (referrer, asset key) -> total commission earned
+pub const REFERRAL_EARNINGS: Map<(Addr, String), Asset> = Map::new("referral_earnings");
+
+// The referral_rewards map stores the (referrer, asset key) -> claimable commission
+pub const REFERRAL_REWARDS: Map<(Addr, String), Asset> = Map::new("referral_rewards");
+
+// The token_gates map stores the subscription_plan_id -> token holdings required to subscribe
+pub const TOKEN_GATES: Map<u64, TokenGate> = Map::new("token_gates");
+
+#[cw_serde]
+pub enum Discount {
+    // Percentage off the plan price
+    Percent(u8),
+    // Fixed amount off the plan price, only applies to prices in the same asset
+    Fixed(Asset),
+}
+
+impl Discount {
+    // Applies the discount to the given price
+    pub fn apply(&self, price: &Asset) -> Result<Asset, ContractError> {
+        let amount = match self {
+            Discount::Percent(percent) => price
+                .amount
+                .multiply_ratio(100u128 - *percent as u128, 100u128),
+            Discount::Fixed(discount) => {
+                if discount.info != price.info {
+                    return Err(ContractError::CouponNotApplicable {});
+                }
+                price.amount.saturating_sub(discount.amount)
+            }
+        };
+        Ok(Asset {
+            info: price.info.clone(),
+            amount,
+        })
+    }
+}
+
+#[cw_serde]
+pub enum CouponDuration {
+    // The discount applies to the first N periods of the subscription
+    Periods(u32),
+    // The discount applies to every period of the subscription
+    Forever,
+}
+
+#[cw_serde]
+pub struct Coupon {
+    // Discount of the coupon
+    pub discount: Discount,
+    // How long the discount applies to a subscription
+    pub duration: CouponDuration,
+    // Maximum number of redemptions across all users
+    pub max_redemptions: Option<u32>,
+    // Maximum number of redemptions per user
+    pub per_user_limit: Option<u32>,
+    // When the coupon expires
+    pub expiration: Option<Timestamp>,
+    // Subscription plans the coupon is restricted to
+    pub plan_ids: Option<Vec<u64>>,
+    // Number of times the coupon has been redeemed
+    pub redemptions: u32,
+}
+
+// The coupons map stores the (organization_id, code_hash) -> coupon
+pub const COUPONS: Map<(u32, String), Coupon> = Map::new("coupons");
+
+// The coupon_redemptions map stores the (organization_id, code_hash, user) -> number of redemptions
+pub const COUPON_REDEMPTIONS: Map<(u32, String, Addr), u32> = Map::new("coupon_redemptions");
+
+#[cw_serde]
+pub struct Approval {
+    // The address that is allowed to transfer the subscription token
+    pub spender: Addr,
+    // When the approval expires
+    pub expires: Expiration,
+}
+
+// The token_approvals map stores the subscription_id -> approvals of the subscription token
+pub const TOKEN_APPROVALS: Map<u64, Vec<Approval>> = Map::new("token_approvals");
+
+// The operators map stores the (owner, operator) -> expiration of the operator approval
+pub const OPERATORS: Map<(Addr, Addr), Expiration> = Map::new("operators");
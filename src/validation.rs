@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use crate::asset::Asset;
+use crate::error::ContractError;
+use crate::state::ValidationLimits;
+
+fn too_long(value: &str, max: u32) -> bool {
+    value.chars().count() > max as usize
+}
+
+pub fn validate_name(limits: &ValidationLimits, name: &str) -> Result<(), ContractError> {
+    if name.trim().is_empty() {
+        return Err(ContractError::EmptyName {});
+    }
+    if too_long(name, limits.max_name_length) {
+        return Err(ContractError::NameTooLong {});
+    }
+    Ok(())
+}
+
+pub fn validate_description(
+    limits: &ValidationLimits,
+    description: &str,
+) -> Result<(), ContractError> {
+    if too_long(description, limits.max_description_length) {
+        return Err(ContractError::DescriptionTooLong {});
+    }
+    Ok(())
+}
+
+// Websites must be http(s) URLs with a host and no whitespace
+pub fn validate_url(limits: &ValidationLimits, url: &str) -> Result<(), ContractError> {
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .and_then(|rest| rest.split(['/', '?', '#']).next())
+        .unwrap_or_default();
+    if host.is_empty()
+        || !host.contains('.')
+        || url.chars().any(char::is_whitespace)
+        || too_long(url, limits.max_url_length)
+    {
+        return Err(ContractError::InvalidUrl {});
+    }
+    Ok(())
+}
+
+pub fn validate_metadata(
+    limits: &ValidationLimits,
+    metadata: &Option<BTreeMap<String, String>>,
+) -> Result<(), ContractError> {
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => return Ok(()),
+    };
+    if metadata.len() > limits.max_metadata_entries as usize {
+        return Err(ContractError::TooManyMetadataEntries {});
+    }
+    if metadata.iter().any(|(key, value)| {
+        key.is_empty()
+            || too_long(key, limits.max_metadata_key_length)
+            || too_long(value, limits.max_metadata_value_length)
+    }) {
+        return Err(ContractError::MetadataTooLong {});
+    }
+    Ok(())
+}
+
+// Features are short identifiers checked against the name limits
+pub fn validate_features(
+    limits: &ValidationLimits,
+    features: &Option<Vec<String>>,
+) -> Result<(), ContractError> {
+    let features = match features {
+        Some(features) => features,
+        None => return Ok(()),
+    };
+    if features.len() > limits.max_features as usize {
+        return Err(ContractError::TooManyFeatures {});
+    }
+    if features
+        .iter()
+        .any(|feature| feature.trim().is_empty() || too_long(feature, limits.max_name_length))
+    {
+        return Err(ContractError::InvalidFeature {});
+    }
+    Ok(())
+}
+
+pub fn validate_prices(limits: &ValidationLimits, prices: &[Asset]) -> Result<(), ContractError> {
+    if let Some(max_price) = limits.max_price {
+        if prices.iter().any(|price| price.amount > max_price) {
+            return Err(ContractError::PriceTooLarge {});
+        }
+    }
+    Ok(())
+}
+
+// Handles are normalized to lowercase slugs of letters, digits and inner hyphens
+pub fn normalize_handle(handle: &str) -> Result<String, ContractError> {
+    let handle = handle.trim().to_lowercase();
+    if !(3..=32).contains(&handle.len())
+        || handle.starts_with('-')
+        || handle.ends_with('-')
+        || !handle
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err(ContractError::InvalidHandle {});
+    }
+    Ok(handle)
+}
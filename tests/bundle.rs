@@ -0,0 +1,542 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::Asset,
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
+    state::{
+        BundleComponent, BundleSubscription, DurationUnit, PlanAccess, TokenGate, TokenRequirement,
+    },
+    ContractError,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+fn create_bundle(
+    app: &mut App,
+    subscription_hub: &Addr,
+    components: Vec<(u64, u8)>,
+    prices: Vec<u128>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateBundle {
+            organization_id: 1,
+            name: "Pro Bundle".to_string(),
+            components: components
+                .into_iter()
+                .map(|(plan_id, share)| BundleComponent { plan_id, share })
+                .collect(),
+            prices: prices.into_iter().map(native_price).collect(),
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn execute(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    msg: ExecuteMsg,
+    amount: u128,
+) -> Result<(), String> {
+    let funds = if amount > 0 {
+        coins(amount, DENOM)
+    } else {
+        vec![]
+    };
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &msg,
+        &funds,
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn create_refundable_subscription_plan(app: &mut App, subscription_hub: &Addr) {
+    execute(
+        app,
+        subscription_hub,
+        ORGANIZATION,
+        ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Refundable Plan".to_string(),
+            description: "Refundable plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        0,
+    )
+    .unwrap();
+}
+
+fn query_revenue(app: &App, subscription_hub: &Addr, organization_id: u32) -> Uint128 {
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id },
+        )
+        .unwrap();
+    res.into_iter().map(|asset| asset.amount).sum()
+}
+
+fn query_is_subscribed(app: &App, subscription_hub: &Addr, plan_id: u64) -> bool {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_bundle() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_organization(&mut app, &subscription_hub, ORGANIZATION2);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION2, 2, true);
+    create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(1, 60), (2, 40)],
+        vec![15_000],
+    )
+    .unwrap();
+
+    // The bundle is sold once the other organization approves it
+    let subscribe = ExecuteMsg::SubscribeBundle {
+        bundle_id: 1,
+        beneficiary: None,
+    };
+    let err = execute(&mut app, &subscription_hub, USER, subscribe.clone(), 15_000).unwrap_err();
+    assert_eq!(err, ContractError::BundleNotApproved {}.to_string());
+
+    let approve = ExecuteMsg::ApproveBundle {
+        bundle_id: 1,
+        organization_id: 2,
+    };
+    let err = execute(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        approve.clone(),
+        0,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    execute(&mut app, &subscription_hub, ORGANIZATION2, approve, 0).unwrap();
+
+    // Subscribing creates a subscription for every plan and splits the revenue
+    let err = execute(&mut app, &subscription_hub, USER, subscribe.clone(), 10_000).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPayment {}.to_string());
+    execute(&mut app, &subscription_hub, USER, subscribe, 15_000).unwrap();
+    assert!(query_is_subscribed(&app, &subscription_hub, 1));
+    assert!(query_is_subscribed(&app, &subscription_hub, 2));
+    assert_eq!(
+        query_revenue(&app, &subscription_hub, 1),
+        Uint128::new(9_000)
+    );
+    assert_eq!(
+        query_revenue(&app, &subscription_hub, 2),
+        Uint128::new(6_000)
+    );
+
+    let res: BundleSubscription = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::BundleSubscription {
+                bundle_subscription_id: 1,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.subscription_ids, vec![1, 2]);
+
+    // Linked subscriptions are managed through the bundle
+    let err = execute(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::CancelPlan { plan_id: 1 },
+        0,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BundledSubscription {}.to_string());
+    let err = execute(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::RenewSubscription {
+            subscription_id: 2,
+            max_price: None,
+        },
+        10_000,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BundledSubscription {}.to_string());
+
+    // Renewing the bundle extends every subscription
+    let expiration = res.expiration;
+    execute(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::RenewBundle {
+            bundle_subscription_id: 1,
+        },
+        15_000,
+    )
+    .unwrap();
+    for subscription_id in [1, 2] {
+        let res: SubscriptionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                subscription_hub.clone(),
+                &QueryMsg::Subscription { subscription_id },
+            )
+            .unwrap();
+        assert_eq!(res.data.expiration, expiration.plus_seconds(MONTH));
+    }
+    assert_eq!(
+        query_revenue(&app, &subscription_hub, 1),
+        Uint128::new(18_000)
+    );
+    assert_eq!(
+        query_revenue(&app, &subscription_hub, 2),
+        Uint128::new(12_000)
+    );
+
+    // Canceling the bundle cancels every subscription
+    let cancel = ExecuteMsg::CancelBundle {
+        bundle_subscription_id: 1,
+    };
+    let err = execute(&mut app, &subscription_hub, USER2, cancel.clone(), 0).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    execute(&mut app, &subscription_hub, USER, cancel.clone(), 0).unwrap();
+    assert!(!query_is_subscribed(&app, &subscription_hub, 1));
+    assert!(!query_is_subscribed(&app, &subscription_hub, 2));
+
+    let err = execute(&mut app, &subscription_hub, USER, cancel, 0).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyCanceled {}.to_string());
+}
+
+#[test]
+fn test_bundle_access() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let cw20 = instantiate_mock_cw20(&mut app, vec![(USER, 100)]);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(1, 50), (2, 50)],
+        vec![15_000],
+    )
+    .unwrap();
+
+    // The restrictions of the component plans apply to the bundle
+    execute(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        ExecuteMsg::SetPlanAccess {
+            plan_id: 2,
+            access: PlanAccess::Allowlist,
+        },
+        0,
+    )
+    .unwrap();
+    execute(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        ExecuteMsg::SetTokenGate {
+            plan_id: 1,
+            gate: Some(TokenGate {
+                requirement: TokenRequirement::Cw20 {
+                    address: cw20,
+                    min_balance: Uint128::new(50),
+                },
+                holder_discount: None,
+            }),
+        },
+        0,
+    )
+    .unwrap();
+    execute(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        ExecuteMsg::UpdatePlanAllowlist {
+            plan_id: 2,
+            add: vec![USER2.to_string()],
+            remove: vec![],
+        },
+        0,
+    )
+    .unwrap();
+
+    let subscribe = ExecuteMsg::SubscribeBundle {
+        bundle_id: 1,
+        beneficiary: None,
+    };
+    let err = execute(&mut app, &subscription_hub, USER, subscribe.clone(), 15_000).unwrap_err();
+    assert_eq!(err, ContractError::NotAllowed {}.to_string());
+    let err = execute(
+        &mut app,
+        &subscription_hub,
+        USER2,
+        subscribe.clone(),
+        15_000,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TokenGateNotMet {}.to_string());
+
+    execute(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        ExecuteMsg::UpdatePlanAllowlist {
+            plan_id: 2,
+            add: vec![USER.to_string()],
+            remove: vec![],
+        },
+        0,
+    )
+    .unwrap();
+    execute(&mut app, &subscription_hub, USER, subscribe, 15_000).unwrap();
+    assert!(query_is_subscribed(&app, &subscription_hub, 1));
+    assert!(query_is_subscribed(&app, &subscription_hub, 2));
+}
+
+#[test]
+fn test_cancel_bundle() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_refundable_subscription_plan(&mut app, &subscription_hub);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, false);
+    create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(1, 60), (2, 40)],
+        vec![15_000],
+    )
+    .unwrap();
+    create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(2, 50), (3, 50)],
+        vec![10_000],
+    )
+    .unwrap();
+
+    // The share of the refundable component is held until it is earned
+    for (subscriber, bundle_id, amount) in [(USER, 1, 15_000), (USER2, 2, 10_000)] {
+        execute(
+            &mut app,
+            &subscription_hub,
+            subscriber,
+            ExecuteMsg::SubscribeBundle {
+                bundle_id,
+                beneficiary: None,
+            },
+            amount,
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        query_revenue(&app, &subscription_hub, 1),
+        Uint128::new(16_000)
+    );
+
+    // Bundles with a component that is not cancelable cannot be canceled
+    let err = execute(
+        &mut app,
+        &subscription_hub,
+        USER2,
+        ExecuteMsg::CancelBundle {
+            bundle_subscription_id: 2,
+        },
+        0,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotCancelable {}.to_string());
+
+    // Canceling after half of the period credits the unused half of the refundable share
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH / 2));
+    execute(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::CancelBundle {
+            bundle_subscription_id: 1,
+        },
+        0,
+    )
+    .unwrap();
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(985_000));
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(4_500)]
+    );
+    assert_eq!(
+        query_revenue(&app, &subscription_hub, 1),
+        Uint128::new(20_500)
+    );
+}
+
+#[test]
+fn test_renew_bundle_keeps_payer() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_refundable_subscription_plan(&mut app, &subscription_hub);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(1, 60), (2, 40)],
+        vec![15_000],
+    )
+    .unwrap();
+    execute(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::SubscribeBundle {
+            bundle_id: 1,
+            beneficiary: None,
+        },
+        15_000,
+    )
+    .unwrap();
+
+    // Anyone can renew the bundle but does not become its payer
+    execute(
+        &mut app,
+        &subscription_hub,
+        USER3,
+        ExecuteMsg::RenewBundle {
+            bundle_subscription_id: 1,
+        },
+        15_000,
+    )
+    .unwrap();
+    let res: BundleSubscription = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::BundleSubscription {
+                bundle_subscription_id: 1,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.payer, USER);
+
+    let cancel = ExecuteMsg::CancelBundle {
+        bundle_subscription_id: 1,
+    };
+    let err = execute(&mut app, &subscription_hub, USER3, cancel.clone(), 0).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    // The refund of both periods goes to the original payer
+    execute(&mut app, &subscription_hub, USER, cancel, 0).unwrap();
+    assert_eq!(query_credits(&app, &subscription_hub, USER3), vec![]);
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(18_000)]
+    );
+}
+
+#[test]
+fn test_invalid_bundle() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    // The shares have to add up to the whole price
+    let err = create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(1, 50), (2, 40)],
+        vec![15_000],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidBundle {}.to_string());
+
+    // A bundle needs several different plans
+    let err = create_bundle(&mut app, &subscription_hub, vec![(1, 100)], vec![15_000]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidBundle {}.to_string());
+    let err = create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(1, 50), (1, 50)],
+        vec![15_000],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidBundle {}.to_string());
+
+    // A bundle has to be priced
+    let err =
+        create_bundle(&mut app, &subscription_hub, vec![(1, 50), (2, 50)], vec![]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPrices {}.to_string());
+
+    // Bundles of a single organization are sold right away
+    create_bundle(
+        &mut app,
+        &subscription_hub,
+        vec![(1, 50), (2, 50)],
+        vec![15_000],
+    )
+    .unwrap();
+    execute(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::SubscribeBundle {
+            bundle_id: 1,
+            beneficiary: None,
+        },
+        15_000,
+    )
+    .unwrap();
+}
@@ -1,3 +1,6 @@
+// The baseline tests pass empty funds as `&vec![]` and compare booleans with `assert_eq!`
+#![allow(clippy::useless_vec, clippy::bool_assert_comparison)]
+
 pub mod helpers;
 use helpers::*;
 
@@ -0,0 +1,187 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::Asset,
+    msg::{ExecuteMsg, PlanCapacityResponse, QueryMsg, SubscriptionResponse},
+    state::DurationUnit,
+    ContractError,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+fn change_plan(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    subscription_id: u64,
+    plan_id: u64,
+    amount: u128,
+) -> Result<(), String> {
+    let funds = if amount > 0 {
+        coins(amount, DENOM)
+    } else {
+        vec![]
+    };
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::ChangePlan {
+            subscription_id,
+            plan_id,
+            max_price: None,
+            proof: None,
+        },
+        &funds,
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn is_subscribed(app: &App, subscription_hub: &Addr, plan_id: u64) -> bool {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id,
+            },
+        )
+        .unwrap()
+}
+
+fn active_subscribers(app: &App, subscription_hub: &Addr, plan_id: u64) -> u32 {
+    let res: PlanCapacityResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::PlanCapacity { plan_id },
+        )
+        .unwrap();
+    res.active_subscribers
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH / 2));
+    change_plan(&mut app, &subscription_hub, USER, 1, 2, 10_000).unwrap();
+
+    // The subscription moved to the new plan and starts a new period
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.plan_id, 2);
+    assert_eq!(res.data.started, app.block_info().time);
+    assert_eq!(
+        res.data.expiration,
+        app.block_info().time.plus_seconds(MONTH)
+    );
+    assert!(!is_subscribed(&app, &subscription_hub, 1));
+    assert!(is_subscribed(&app, &subscription_hub, 2));
+
+    // The seat moved along with the subscription
+    assert_eq!(active_subscribers(&app, &subscription_hub, 1), 0);
+    assert_eq!(active_subscribers(&app, &subscription_hub, 2), 1);
+}
+
+#[test]
+fn test_refund_unused_time() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Refundable Plan".to_string(),
+            description: "Refundable plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // The unused half of the period is credited and pays for part of the new plan
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH / 2));
+    let balance = query_native_balance(&app, USER);
+    change_plan(&mut app, &subscription_hub, USER, 1, 2, 5_000).unwrap();
+    assert_eq!(
+        query_native_balance(&app, USER),
+        balance - Uint128::new(5_000)
+    );
+    assert_eq!(query_credits(&app, &subscription_hub, USER), vec![]);
+
+    // The organization keeps the used half of the old plan and the price of the new one
+    let revenue: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(revenue, vec![native_price(15_000)]);
+}
+
+#[test]
+fn test_invalid_plan_change() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_organization(&mut app, &subscription_hub, ORGANIZATION2);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION2, 2, true);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // Only the subscriber can change the plan
+    let err = change_plan(&mut app, &subscription_hub, USER2, 1, 2, 10_000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    // The new plan must be another plan of the same organization
+    let err = change_plan(&mut app, &subscription_hub, USER, 1, 1, 10_000).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPlanChange {}.to_string());
+    let err = change_plan(&mut app, &subscription_hub, USER, 1, 3, 10_000).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPlanChange {}.to_string());
+
+    // The new plan must be paid for
+    let err = change_plan(&mut app, &subscription_hub, USER, 1, 2, 0).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPayment {}.to_string());
+
+    // Expired subscriptions cannot change their plan
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH + 1));
+    let err = change_plan(&mut app, &subscription_hub, USER, 1, 2, 10_000).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyExpired {}.to_string());
+}
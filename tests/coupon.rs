@@ -0,0 +1,321 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::Executor;
+use sha2::{Digest, Sha256};
+use subscription_hub::{
+    msg::{CouponResponse, ExecuteMsg, QueryMsg, SubscriptionResponse},
+    state::{CouponDuration, Discount},
+    ContractError,
+};
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateCoupon {
+            organization_id: 1,
+            code: "LAUNCH20".to_string(),
+            discount: Discount::Percent(20),
+            duration: CouponDuration::Periods(3),
+            max_redemptions: Some(2),
+            per_user_limit: None,
+            expiration: None,
+            plan_ids: Some(vec![1]),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: Some("LAUNCH20".to_string()),
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(8_000, DENOM),
+    )
+    .unwrap();
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.price, Some(native_price(8_000)));
+    let coupon = res.data.coupon.unwrap();
+    assert_eq!(coupon.discount, Discount::Percent(20));
+    assert_eq!(coupon.remaining_periods, Some(2));
+    assert_ne!(coupon.code_hash, "LAUNCH20");
+    // The code hash is salted, it is not the plain hash of the code
+    assert_ne!(coupon.code_hash, hex::encode(Sha256::digest("LAUNCH20")));
+
+    let res: CouponResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::CouponInfo {
+                organization_id: 1,
+                code: "LAUNCH20".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.code_hash, coupon.code_hash);
+    assert_eq!(res.data.redemptions, 1);
+    assert_eq!(res.remaining_redemptions, Some(1));
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: Some("LAUNCH20".to_string()),
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(8_000, DENOM),
+    )
+    .unwrap();
+
+    // The coupon has no redemptions left
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub,
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: Some("LAUNCH20".to_string()),
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(8_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::CouponExhausted {}.to_string()
+    );
+}
+
+#[test]
+fn test_per_user_limit() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateCoupon {
+            organization_id: 1,
+            code: "WELCOME".to_string(),
+            discount: Discount::Fixed(native_price(2_500)),
+            duration: CouponDuration::Forever,
+            max_redemptions: None,
+            per_user_limit: Some(1),
+            expiration: None,
+            plan_ids: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: Some("WELCOME".to_string()),
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(7_500, DENOM),
+    )
+    .unwrap();
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.price, Some(native_price(7_500)));
+    assert_eq!(res.data.coupon.unwrap().remaining_periods, None);
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: Some("WELCOME".to_string()),
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(7_500, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::CouponLimitReached {}.to_string()
+    );
+}
+
+#[test]
+fn test_invalid_coupon() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateCoupon {
+            organization_id: 1,
+            code: "PLANONE".to_string(),
+            discount: Discount::Percent(50),
+            duration: CouponDuration::Periods(1),
+            max_redemptions: None,
+            per_user_limit: None,
+            expiration: Some(app.block_info().time.plus_seconds(3600)),
+            plan_ids: Some(vec![1]),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Unknown coupon code
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: Some("UNKNOWN".to_string()),
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::InvalidCoupon {}.to_string()
+    );
+
+    // Coupon restricted to another plan
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 2,
+                beneficiary: None,
+                coupon: Some("PLANONE".to_string()),
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(5_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::CouponNotApplicable {}.to_string()
+    );
+
+    // Move time past the coupon expiration
+    app.update_block(|block| block.time = block.time.plus_seconds(3600));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: Some("PLANONE".to_string()),
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(5_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::CouponExpired {}.to_string()
+    );
+}
+
+#[test]
+fn test_invalid_organization_owner() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ORGANIZATION2),
+            subscription_hub,
+            &ExecuteMsg::CreateCoupon {
+                organization_id: 1,
+                code: "LAUNCH20".to_string(),
+                discount: Discount::Percent(20),
+                duration: CouponDuration::Forever,
+                max_redemptions: None,
+                per_user_limit: None,
+                expiration: None,
+                plan_ids: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized {}.to_string()
+    );
+}
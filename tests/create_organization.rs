@@ -1,3 +1,6 @@
+// The baseline tests pass empty funds as `&vec![]`
+#![allow(clippy::useless_vec)]
+
 pub mod helpers;
 use helpers::*;
 
@@ -18,6 +21,7 @@ fn test_happy_path() {
         &ExecuteMsg::CreateOrganization {
             name: "Test Organization".to_string(),
             description: "Test organization is the best".to_string(),
+            handle: None,
             website: None,
             metadata: None,
         },
@@ -34,6 +38,7 @@ fn test_happy_path() {
         &ExecuteMsg::CreateOrganization {
             name: "Second Test Organization".to_string(),
             description: "Second test organization is the best".to_string(),
+            handle: None,
             website: None,
             metadata: Some(organization2_metadata.clone()),
         },
@@ -1,7 +1,10 @@
+// The baseline tests pass empty funds as `&vec![]`
+#![allow(clippy::useless_vec)]
+
 pub mod helpers;
 use helpers::*;
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::Addr;
 use cw_multi_test::Executor;
 use subscription_hub::{
     msg::{ExecuteMsg, QueryMsg, SubscriptionPlanResponse},
@@ -23,7 +26,10 @@ fn test_happy_path() {
             organization_id: 1,
             name: "Test Plan".to_string(),
             description: "Test plan is the best".to_string(),
-            price: Uint128::new(10_000),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
             duration: 1,
             duration_unit: DurationUnit::Month,
             features: Some(vec![
@@ -33,6 +39,11 @@ fn test_happy_path() {
             metadata: None,
             cancelable: false,
             refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
         },
         &vec![],
     )
@@ -46,7 +57,10 @@ fn test_happy_path() {
             organization_id: 1,
             name: "Second Test Plan".to_string(),
             description: "Second test plan is the best".to_string(),
-            price: Uint128::new(50_000),
+            prices: vec![native_price(50_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
             duration: 6,
             duration_unit: DurationUnit::Month,
             features: Some(vec![
@@ -58,6 +72,11 @@ fn test_happy_path() {
             metadata: None,
             cancelable: false,
             refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
         },
         &vec![],
     )
@@ -71,7 +90,10 @@ fn test_happy_path() {
             organization_id: 1,
             name: "Third Test Plan".to_string(),
             description: "Third test plan is the best".to_string(),
-            price: Uint128::new(250_000),
+            prices: vec![native_price(250_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
             duration: 1,
             duration_unit: DurationUnit::Year,
             features: Some(vec![
@@ -87,6 +109,11 @@ fn test_happy_path() {
             metadata: None,
             cancelable: false,
             refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
         },
         &vec![],
     )
@@ -101,7 +128,7 @@ fn test_happy_path() {
         .unwrap();
     assert_eq!(res.data.name, "Test Plan");
     assert_eq!(res.data.description, "Test plan is the best");
-    assert_eq!(res.data.price, Uint128::new(10_000));
+    assert_eq!(res.data.prices, vec![native_price(10_000)]);
     assert_eq!(res.data.duration, 1);
     assert_eq!(res.data.duration_unit, DurationUnit::Month);
 
@@ -114,7 +141,7 @@ fn test_happy_path() {
         .unwrap();
     assert_eq!(res.data.name, "Second Test Plan");
     assert_eq!(res.data.description, "Second test plan is the best");
-    assert_eq!(res.data.price, Uint128::new(50_000));
+    assert_eq!(res.data.prices, vec![native_price(50_000)]);
     assert_eq!(res.data.duration, 6);
     assert_eq!(res.data.duration_unit, DurationUnit::Month);
 
@@ -127,7 +154,7 @@ fn test_happy_path() {
         .unwrap();
     assert_eq!(res.data.name, "Third Test Plan");
     assert_eq!(res.data.description, "Third test plan is the best");
-    assert_eq!(res.data.price, Uint128::new(250_000));
+    assert_eq!(res.data.prices, vec![native_price(250_000)]);
     assert_eq!(res.data.duration, 1);
     assert_eq!(res.data.duration_unit, DurationUnit::Year);
     assert_eq!(res.data.features.unwrap().len(), 8);
@@ -158,7 +185,10 @@ fn test_invalid_organization_owner() {
                 organization_id: 1,
                 name: "Test Plan".to_string(),
                 description: "Test plan is the best".to_string(),
-                price: Uint128::new(10_000),
+                prices: vec![native_price(10_000)],
+                reference_price: None,
+                kind: None,
+                dunning: None,
                 duration: 1,
                 duration_unit: DurationUnit::Month,
                 features: Some(vec![
@@ -168,6 +198,11 @@ fn test_invalid_organization_owner() {
                 metadata: None,
                 cancelable: false,
                 refundable: false,
+                transferable: false,
+                pausable: false,
+                max_pause_duration: None,
+                max_subscribers: None,
+                waitlist: false,
             },
             &vec![],
         )
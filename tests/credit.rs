@@ -0,0 +1,355 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Coin, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::{Asset, AssetInfo},
+    msg::{CreditEntryResponse, ExecuteMsg, QueryMsg},
+    state::{CreditEntryKind, DunningPolicy, DurationUnit},
+    ContractError,
+};
+
+fn deposit_credit(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    recipient: Option<&str>,
+    funds: &[Coin],
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::DepositCredit {
+            recipient: recipient.map(|recipient| recipient.to_string()),
+        },
+        funds,
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn withdraw_credit(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    amount: Option<u128>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::WithdrawCredit {
+            asset: AssetInfo::Native {
+                denom: DENOM.to_string(),
+            },
+            amount: amount.map(Uint128::new),
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn subscribe(
+    app: &mut App,
+    subscription_hub: &Addr,
+    subscriber: &str,
+    funds: &[Coin],
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(subscriber),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        funds,
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn renew(
+    app: &mut App,
+    subscription_hub: &Addr,
+    subscription_id: u64,
+    funds: &[Coin],
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id,
+            max_price: None,
+        },
+        funds,
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn query_credit_ledger(
+    app: &App,
+    subscription_hub: &Addr,
+    user: &str,
+    start_after: Option<u64>,
+) -> Vec<CreditEntryResponse> {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::CreditLedger {
+                user_address: user.to_string(),
+                start_after,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+fn query_revenue(app: &App, subscription_hub: &Addr) -> Uint128 {
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    res.into_iter().map(|asset| asset.amount).sum()
+}
+
+#[test]
+fn test_deposit_and_withdraw_credit() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    let err = deposit_credit(&mut app, &subscription_hub, USER, None, &[]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPayment {}.to_string());
+
+    // Top up the own credit and the credit of another user
+    let balance = query_native_balance(&app, USER);
+    deposit_credit(
+        &mut app,
+        &subscription_hub,
+        USER,
+        None,
+        &coins(15_000, DENOM),
+    )
+    .unwrap();
+    deposit_credit(
+        &mut app,
+        &subscription_hub,
+        USER,
+        Some(USER2),
+        &coins(5_000, DENOM),
+    )
+    .unwrap();
+    assert_eq!(
+        query_native_balance(&app, USER),
+        balance - Uint128::new(20_000)
+    );
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(15_000)]
+    );
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER2),
+        vec![native_price(5_000)]
+    );
+
+    // Withdraw part of the credit and then the rest
+    let err = withdraw_credit(&mut app, &subscription_hub, USER, Some(20_000)).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientCredit {}.to_string());
+    withdraw_credit(&mut app, &subscription_hub, USER, Some(10_000)).unwrap();
+    withdraw_credit(&mut app, &subscription_hub, USER, None).unwrap();
+    assert_eq!(
+        query_native_balance(&app, USER),
+        balance - Uint128::new(5_000)
+    );
+    assert_eq!(query_credits(&app, &subscription_hub, USER), vec![]);
+    let err = withdraw_credit(&mut app, &subscription_hub, USER, None).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientCredit {}.to_string());
+
+    // Every top up and withdrawal is recorded in the ledger
+    let ledger = query_credit_ledger(&app, &subscription_hub, USER, None);
+    assert_eq!(ledger.len(), 3);
+    assert_eq!(ledger[0].id, 1);
+    assert_eq!(ledger[0].data.kind, CreditEntryKind::Credit);
+    assert_eq!(ledger[0].data.amount, native_price(15_000));
+    assert_eq!(ledger[0].data.reason, "deposit");
+    assert_eq!(ledger[1].data.kind, CreditEntryKind::Debit);
+    assert_eq!(ledger[1].data.amount, native_price(10_000));
+    assert_eq!(ledger[2].data.amount, native_price(5_000));
+    assert_eq!(ledger[2].data.reason, "withdrawal");
+    let ledger = query_credit_ledger(&app, &subscription_hub, USER, Some(2));
+    assert_eq!(ledger.len(), 1);
+    assert_eq!(ledger[0].id, 3);
+}
+
+#[test]
+fn test_refunds_to_credit() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Prepaid Plan".to_string(),
+            description: "Prepaid plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: Some(DunningPolicy {
+                grace_period: 0,
+                retry_schedule: vec![],
+            }),
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Both subscriptions have a prepaid balance
+    for (subscriber, subscription_id) in [(USER, 1), (USER2, 2)] {
+        subscribe(
+            &mut app,
+            &subscription_hub,
+            subscriber,
+            &coins(10_000, DENOM),
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(subscriber),
+            subscription_hub.clone(),
+            &ExecuteMsg::TopUp { subscription_id },
+            &coins(5_000, DENOM),
+        )
+        .unwrap();
+    }
+
+    // Canceling credits the unused time and the balance to the payer
+    app.update_block(|block| block.time = block.time.plus_seconds(15 * 24 * 60 * 60));
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(10_000)]
+    );
+    let ledger = query_credit_ledger(&app, &subscription_hub, USER, None);
+    assert_eq!(ledger.len(), 2);
+    assert_eq!(ledger[0].data.kind, CreditEntryKind::Credit);
+    assert_eq!(ledger[0].data.amount, native_price(5_000));
+    assert_eq!(ledger[0].data.reason, "refund");
+    assert_eq!(ledger[0].data.subscription_id, Some(1));
+    assert_eq!(ledger[1].data.amount, native_price(5_000));
+    assert_eq!(ledger[1].data.reason, "balance");
+    assert_eq!(ledger[1].data.subscription_id, Some(1));
+
+    // A lapsed subscription credits its balance to the payer
+    app.update_block(|block| block.time = block.time.plus_seconds(16 * 24 * 60 * 60));
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 2 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER2),
+        vec![native_price(5_000)]
+    );
+    let ledger = query_credit_ledger(&app, &subscription_hub, USER2, None);
+    assert_eq!(ledger.len(), 1);
+    assert_eq!(ledger[0].data.reason, "balance");
+    assert_eq!(ledger[0].data.subscription_id, Some(2));
+
+    // The credited refunds can be withdrawn
+    let balance = query_native_balance(&app, USER);
+    withdraw_credit(&mut app, &subscription_hub, USER, None).unwrap();
+    assert_eq!(
+        query_native_balance(&app, USER),
+        balance + Uint128::new(10_000)
+    );
+}
+
+#[test]
+fn test_subscribe_with_credit() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    // Without credit the subscription has to be paid
+    let err = subscribe(&mut app, &subscription_hub, USER, &[]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPayment {}.to_string());
+
+    // The credit pays the whole price
+    deposit_credit(
+        &mut app,
+        &subscription_hub,
+        USER,
+        None,
+        &coins(14_000, DENOM),
+    )
+    .unwrap();
+    subscribe(&mut app, &subscription_hub, USER, &[]).unwrap();
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(4_000)]
+    );
+    assert_eq!(query_revenue(&app, &subscription_hub), Uint128::new(10_000));
+
+    // The credit pays part of the renewal and the funds the rest
+    renew(&mut app, &subscription_hub, 1, &coins(6_000, DENOM)).unwrap();
+    assert_eq!(query_credits(&app, &subscription_hub, USER), vec![]);
+    assert_eq!(query_revenue(&app, &subscription_hub), Uint128::new(20_000));
+
+    // The spent credit is recorded against the subscription
+    let ledger = query_credit_ledger(&app, &subscription_hub, USER, None);
+    assert_eq!(ledger.len(), 3);
+    assert_eq!(ledger[1].data.kind, CreditEntryKind::Debit);
+    assert_eq!(ledger[1].data.amount, native_price(10_000));
+    assert_eq!(ledger[1].data.reason, "subscription");
+    assert_eq!(ledger[1].data.subscription_id, Some(1));
+    assert_eq!(ledger[2].data.amount, native_price(4_000));
+    assert_eq!(ledger[2].data.reason, "renewal");
+    assert_eq!(ledger[2].data.subscription_id, Some(1));
+
+    // A full payment leaves the credit untouched
+    deposit_credit(
+        &mut app,
+        &subscription_hub,
+        USER2,
+        None,
+        &coins(5_000, DENOM),
+    )
+    .unwrap();
+    subscribe(&mut app, &subscription_hub, USER2, &coins(10_000, DENOM)).unwrap();
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER2),
+        vec![native_price(5_000)]
+    );
+    assert_eq!(query_revenue(&app, &subscription_hub), Uint128::new(30_000));
+}
@@ -0,0 +1,268 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::Addr;
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::{
+        AllNftInfoResponse, ContractInfoResponse, ExecuteMsg, NumTokensResponse, OwnerOfResponse,
+        QueryMsg, SubscriptionResponse, TokensResponse,
+    },
+    ContractError,
+};
+
+fn query_tokens(app: &App, subscription_hub: &Addr, owner: &str) -> Vec<String> {
+    let res: TokensResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Tokens {
+                owner: owner.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    res.tokens
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let res: ContractInfoResponse = app
+        .wrap()
+        .query_wasm_smart(subscription_hub.clone(), &QueryMsg::ContractInfo {})
+        .unwrap();
+    assert_eq!(res.name, "Subscription Hub");
+
+    let res: NumTokensResponse = app
+        .wrap()
+        .query_wasm_smart(subscription_hub.clone(), &QueryMsg::NumTokens {})
+        .unwrap();
+    assert_eq!(res.count, 1);
+
+    let res: AllNftInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::AllNftInfo {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.access.owner, USER);
+    assert_eq!(res.info.extension.plan_id, 1);
+    assert_eq!(res.info.extension.plan_name, "Transferable Plan");
+    assert_eq!(res.info.extension.organization_id, 1);
+    assert_eq!(res.info.extension.organization_name, "Test Organization");
+    assert_eq!(
+        res.info.extension.expiration,
+        app.block_info().time.plus_seconds(2592000)
+    );
+
+    let res: TokensResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Tokens {
+                owner: USER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.tokens, vec!["1".to_string()]);
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::TransferNft {
+            recipient: USER2.to_string(),
+            token_id: "1".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The subscription follows the token owner
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.subscriber, USER2);
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::IsSubscribed {
+                user_address: USER2.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(res);
+
+    let res: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.owner, USER2);
+
+    // The token moves between the owners' token lists
+    assert!(query_tokens(&app, &subscription_hub, USER).is_empty());
+    assert_eq!(
+        query_tokens(&app, &subscription_hub, USER2),
+        vec!["1".to_string()]
+    );
+}
+
+#[test]
+fn test_tokens_of_replaced_subscriptions() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+
+    // Subscribe again once the first subscription expired
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    app.update_block(|block| block.time = block.time.plus_seconds(2592001));
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // The owner still owns the token of the replaced subscription
+    let res: NumTokensResponse = app
+        .wrap()
+        .query_wasm_smart(subscription_hub.clone(), &QueryMsg::NumTokens {})
+        .unwrap();
+    assert_eq!(res.count, 2);
+    assert_eq!(
+        query_tokens(&app, &subscription_hub, USER),
+        vec!["1".to_string(), "2".to_string()]
+    );
+    let res: TokensResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Tokens {
+                owner: USER.to_string(),
+                start_after: Some("1".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.tokens, vec!["2".to_string()]);
+}
+
+#[test]
+fn test_approved_transfer() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // Transfer without approval
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub.clone(),
+            &ExecuteMsg::TransferNft {
+                recipient: USER2.to_string(),
+                token_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized {}.to_string()
+    );
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::Approve {
+            spender: USER3.to_string(),
+            token_id: "1".to_string(),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::TransferNft {
+            recipient: USER2.to_string(),
+            token_id: "1".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Approvals are cleared after a transfer
+    let res: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.owner, USER2);
+    assert!(res.approvals.is_empty());
+}
+
+#[test]
+fn test_non_transferable_subscription_plan() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::TransferNft {
+                recipient: USER2.to_string(),
+                token_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::NotTransferable {}.to_string()
+    );
+}
@@ -0,0 +1,279 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Event, Uint128};
+use cw_multi_test::{App, AppResponse, Executor};
+use subscription_hub::{
+    asset::Asset,
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
+    state::{DunningPolicy, DurationUnit, SubscriptionStatus},
+    ContractError,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+const MONTH: u64 = 30 * DAY;
+
+fn create_dunning_subscription_plan(
+    app: &mut App,
+    subscription_hub: &Addr,
+    dunning: DunningPolicy,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Monthly Plan".to_string(),
+            description: "Monthly plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: Some(dunning),
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn dunning_policy() -> DunningPolicy {
+    DunningPolicy {
+        grace_period: 7 * DAY,
+        retry_schedule: vec![DAY, 3 * DAY],
+    }
+}
+
+fn top_up(app: &mut App, subscription_hub: &Addr, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::TopUp { subscription_id: 1 },
+        &coins(amount, DENOM),
+    )
+    .unwrap();
+}
+
+fn process_subscription(app: &mut App, subscription_hub: &Addr) -> AppResponse {
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap()
+}
+
+fn query_subscription(app: &App, subscription_hub: &Addr) -> SubscriptionResponse {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap()
+}
+
+fn is_subscribed(app: &App, subscription_hub: &Addr) -> bool {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap()
+}
+
+fn find_event(res: &AppResponse, ty: &str) -> Option<Event> {
+    res.events
+        .iter()
+        .find(|event| event.ty == format!("wasm-{}", ty))
+        .cloned()
+}
+
+fn event_attribute(event: &Event, key: &str) -> String {
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .unwrap()
+        .value
+        .clone()
+}
+
+#[test]
+fn test_automatic_renewal() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_dunning_subscription_plan(&mut app, &subscription_hub, dunning_policy()).unwrap();
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    top_up(&mut app, &subscription_hub, 10_000);
+    let expiration = query_subscription(&app, &subscription_hub).data.expiration;
+
+    // Nothing happens before the expiration
+    let res = process_subscription(&mut app, &subscription_hub);
+    assert!(find_event(&res, "subscription_renewed").is_none());
+
+    // The next period is paid from the prepaid balance at the expiration
+    app.update_block(|block| block.time = expiration);
+    let res = process_subscription(&mut app, &subscription_hub);
+    assert!(find_event(&res, "subscription_renewed").is_some());
+
+    let res = query_subscription(&app, &subscription_hub);
+    assert_eq!(res.data.status, SubscriptionStatus::Active);
+    assert_eq!(res.data.expiration, expiration.plus_seconds(MONTH));
+    assert_eq!(res.data.balance, Some(native_price(0)));
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(20_000)]);
+}
+
+#[test]
+fn test_retry_after_failed_renewal() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_dunning_subscription_plan(&mut app, &subscription_hub, dunning_policy()).unwrap();
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    let expiration = query_subscription(&app, &subscription_hub).data.expiration;
+
+    // The first attempt fails and the subscription becomes past due
+    app.update_block(|block| block.time = expiration);
+    let res = process_subscription(&mut app, &subscription_hub);
+    assert!(find_event(&res, "subscription_past_due").is_some());
+    let event = find_event(&res, "renewal_failed").unwrap();
+    assert_eq!(event_attribute(&event, "attempt"), "1");
+    assert_eq!(
+        event_attribute(&event, "next_retry"),
+        expiration.plus_seconds(DAY).seconds().to_string()
+    );
+
+    // Access continues during the grace period
+    app.update_block(|block| block.time = block.time.plus_seconds(DAY / 2));
+    assert_eq!(
+        query_subscription(&app, &subscription_hub).data.status,
+        SubscriptionStatus::PastDue
+    );
+    assert!(is_subscribed(&app, &subscription_hub));
+
+    // No attempt is made before the next retry
+    top_up(&mut app, &subscription_hub, 10_000);
+    let res = process_subscription(&mut app, &subscription_hub);
+    assert!(find_event(&res, "subscription_renewed").is_none());
+
+    // The retry succeeds once the balance covers the price
+    app.update_block(|block| block.time = expiration.plus_seconds(DAY));
+    let res = process_subscription(&mut app, &subscription_hub);
+    assert!(find_event(&res, "subscription_renewed").is_some());
+
+    let res = query_subscription(&app, &subscription_hub);
+    assert_eq!(res.data.status, SubscriptionStatus::Active);
+    assert_eq!(res.data.renewal_attempts, 0);
+    assert_eq!(res.data.expiration, expiration.plus_seconds(MONTH));
+}
+
+#[test]
+fn test_lapsed_subscription() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_dunning_subscription_plan(&mut app, &subscription_hub, dunning_policy()).unwrap();
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    top_up(&mut app, &subscription_hub, 5_000);
+    let expiration = query_subscription(&app, &subscription_hub).data.expiration;
+
+    // Every scheduled attempt fails
+    for (offset, attempt) in [(0, "1"), (DAY, "2"), (3 * DAY, "3")] {
+        app.update_block(|block| block.time = expiration.plus_seconds(offset));
+        let res = process_subscription(&mut app, &subscription_hub);
+        let event = find_event(&res, "renewal_failed").unwrap();
+        assert_eq!(event_attribute(&event, "attempt"), attempt);
+    }
+    assert!(is_subscribed(&app, &subscription_hub));
+
+    // The subscription lapses once the grace period is over
+    app.update_block(|block| block.time = expiration.plus_seconds(7 * DAY + 1));
+    assert!(!is_subscribed(&app, &subscription_hub));
+    let res = process_subscription(&mut app, &subscription_hub);
+    assert!(find_event(&res, "subscription_lapsed").is_some());
+
+    // The unused balance is credited to the payer
+    let res = query_subscription(&app, &subscription_hub);
+    assert_eq!(res.data.status, SubscriptionStatus::Lapsed);
+    assert_eq!(res.data.balance, Some(native_price(0)));
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(985_000));
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(5_000)]
+    );
+
+    // A lapsed subscription starts anew when renewed manually
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+    let res = query_subscription(&app, &subscription_hub);
+    assert_eq!(res.data.status, SubscriptionStatus::Active);
+    assert_eq!(
+        res.data.expiration,
+        app.block_info().time.plus_seconds(MONTH)
+    );
+    assert!(is_subscribed(&app, &subscription_hub));
+}
+
+#[test]
+fn test_invalid_dunning_policy() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    let err = create_dunning_subscription_plan(
+        &mut app,
+        &subscription_hub,
+        DunningPolicy {
+            grace_period: 7 * DAY,
+            retry_schedule: vec![3 * DAY, DAY],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDunningPolicy {}.to_string());
+
+    let err = create_dunning_subscription_plan(
+        &mut app,
+        &subscription_hub,
+        DunningPolicy {
+            grace_period: 7 * DAY,
+            retry_schedule: vec![DAY, 8 * DAY],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDunningPolicy {}.to_string());
+}
@@ -0,0 +1,107 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{Addr, StdError};
+use cw_multi_test::App;
+use subscription_hub::{helpers::SubscriptionHubContract, msg::QueryMsg};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+fn has_entitlement(
+    app: &App,
+    subscription_hub: &Addr,
+    organization_id: u32,
+    feature: &str,
+) -> bool {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::HasEntitlement {
+                user_address: USER.to_string(),
+                organization_id,
+                feature: feature.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_has_entitlement() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    assert!(!has_entitlement(
+        &app,
+        &subscription_hub,
+        1,
+        "first_feature"
+    ));
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    assert!(has_entitlement(&app, &subscription_hub, 1, "first_feature"));
+    assert!(has_entitlement(
+        &app,
+        &subscription_hub,
+        1,
+        "second_feature"
+    ));
+    assert!(!has_entitlement(
+        &app,
+        &subscription_hub,
+        1,
+        "third_feature"
+    ));
+    assert!(!has_entitlement(
+        &app,
+        &subscription_hub,
+        2,
+        "first_feature"
+    ));
+
+    // The entitlement ends with the subscription
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH + 1));
+    assert!(!has_entitlement(
+        &app,
+        &subscription_hub,
+        1,
+        "first_feature"
+    ));
+}
+
+#[test]
+fn test_subscription_hub_contract() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hub = SubscriptionHubContract(subscription_hub.clone());
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    let querier = app.wrap();
+    assert!(!hub.is_subscribed(&querier, USER, 1).unwrap());
+    let err = hub.require_subscription(&querier, USER, 1).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("User is not subscribed to the subscription plan")
+    );
+    let err = hub
+        .require_entitlement(&querier, USER, 1, "first_feature")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("User does not have the required entitlement")
+    );
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    let querier = app.wrap();
+    assert!(hub.is_subscribed(&querier, USER, 1).unwrap());
+    assert!(hub
+        .has_entitlement(&querier, USER, 1, "first_feature")
+        .unwrap());
+    hub.require_subscription(&querier, USER, 1).unwrap();
+    hub.require_entitlement(&querier, USER, 1, "first_feature")
+        .unwrap();
+}
@@ -0,0 +1,319 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{attr, coins, Addr, Attribute};
+use cw_multi_test::{App, AppResponse, Executor};
+use cw_utils::Expiration;
+use subscription_hub::{msg::ExecuteMsg, state::DurationUnit};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+// Attributes of the event after the contract address added by the runtime
+fn event_attributes(res: &AppResponse, subscription_hub: &Addr, ty: &str) -> Vec<Attribute> {
+    let event = res
+        .events
+        .iter()
+        .find(|event| event.ty == format!("wasm-{}", ty))
+        .unwrap();
+    assert_eq!(event.attributes[0].key, "_contract_addr");
+    assert_eq!(event.attributes[0].value, subscription_hub.as_str());
+    event.attributes[1..].to_vec()
+}
+
+fn subscription_attributes(app: &App, subscriber: &str) -> Vec<Attribute> {
+    vec![
+        attr("subscription_id", "1"),
+        attr("plan_id", "1"),
+        attr("organization_id", "1"),
+        attr("subscriber", subscriber),
+        attr("timestamp", app.block_info().time.seconds().to_string()),
+    ]
+}
+
+#[test]
+fn test_plan_created() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ORGANIZATION),
+            subscription_hub.clone(),
+            &ExecuteMsg::CreateOrganization {
+                name: "Test Organization".to_string(),
+                description: "Test organization is the best".to_string(),
+                handle: None,
+                website: None,
+                metadata: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "organization_created"),
+        vec![
+            attr("organization_id", "1"),
+            attr("owner", ORGANIZATION),
+            attr("timestamp", app.block_info().time.seconds().to_string()),
+        ]
+    );
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ORGANIZATION),
+            subscription_hub.clone(),
+            &ExecuteMsg::CreateSubscriptionPlan {
+                organization_id: 1,
+                name: "Test Plan".to_string(),
+                description: "Test plan is the best".to_string(),
+                prices: vec![native_price(10_000)],
+                reference_price: None,
+                kind: None,
+                dunning: None,
+                duration: 1,
+                duration_unit: DurationUnit::Month,
+                features: None,
+                metadata: None,
+                cancelable: true,
+                refundable: true,
+                transferable: false,
+                pausable: false,
+                max_pause_duration: None,
+                max_subscribers: None,
+                waitlist: false,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "plan_created"),
+        vec![
+            attr("plan_id", "1"),
+            attr("organization_id", "1"),
+            attr("timestamp", app.block_info().time.seconds().to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_subscription_lifecycle() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Test Plan".to_string(),
+            description: "Test plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Subscribing creates the subscription and collects the payment
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap();
+    let expiration = app.block_info().time.plus_seconds(MONTH);
+    let mut expected = subscription_attributes(&app, USER);
+    expected.extend([
+        attr("payer", USER),
+        attr("expiration", expiration.seconds().to_string()),
+    ]);
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "subscription_created"),
+        expected
+    );
+
+    let mut expected = subscription_attributes(&app, USER);
+    expected.extend([
+        attr("payer", USER),
+        attr("purpose", "subscription"),
+        attr("asset", format!("native:{}", DENOM)),
+        attr("amount", "10000"),
+    ]);
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "payment_received"),
+        expected
+    );
+
+    // Canceling halfway refunds the unused half to the payer
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH / 2));
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::CancelPlan { plan_id: 1 },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "subscription_canceled"),
+        subscription_attributes(&app, USER)
+    );
+
+    let mut expected = subscription_attributes(&app, USER);
+    expected.extend([
+        attr("recipient", USER),
+        attr("reason", "unused_time"),
+        attr("asset", format!("native:{}", DENOM)),
+        attr("amount", "5000"),
+    ]);
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "refund_issued"),
+        expected
+    );
+}
+
+#[test]
+fn test_subscription_transferred() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::TransferNft {
+                recipient: USER2.to_string(),
+                token_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    let mut expected = subscription_attributes(&app, USER2);
+    expected.push(attr("previous_subscriber", USER));
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "subscription_transferred"),
+        expected
+    );
+}
+
+#[test]
+fn test_token_approvals() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    let timestamp = app.block_info().time.seconds().to_string();
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::Approve {
+                spender: USER2.to_string(),
+                token_id: "1".to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "token_approved"),
+        vec![
+            attr("token_id", "1"),
+            attr("owner", USER),
+            attr("spender", USER2),
+            attr("expires", Expiration::Never {}.to_string()),
+            attr("timestamp", &timestamp),
+        ]
+    );
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::Revoke {
+                spender: USER2.to_string(),
+                token_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "token_approval_revoked"),
+        vec![
+            attr("token_id", "1"),
+            attr("owner", USER),
+            attr("spender", USER2),
+            attr("timestamp", &timestamp),
+        ]
+    );
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::ApproveAll {
+                operator: USER3.to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "operator_approved"),
+        vec![
+            attr("owner", USER),
+            attr("operator", USER3),
+            attr("expires", Expiration::Never {}.to_string()),
+            attr("timestamp", &timestamp),
+        ]
+    );
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::RevokeAll {
+                operator: USER3.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        event_attributes(&res, &subscription_hub, "operator_revoked"),
+        vec![
+            attr("owner", USER),
+            attr("operator", USER3),
+            attr("timestamp", &timestamp),
+        ]
+    );
+}
@@ -1,7 +1,19 @@
-use cosmwasm_std::{Addr, Coin, Empty, Uint128};
+// The baseline helpers pass empty funds as `&vec![]`
+#![allow(clippy::useless_vec)]
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
 use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+use cw_storage_plus::{Item, Map};
 use subscription_hub::{
-    msg::{ExecuteMsg, InstantiateMsg},
+    asset::{Asset, AssetInfo},
+    msg::{
+        Cw20ReceiveMsg, ExecuteMsg, InstantiateMsg, OraclePriceResponse, OracleQueryMsg, QueryMsg,
+        SubscriptionHookMsg, TokensResponse,
+    },
     state::DurationUnit,
 };
 
@@ -10,6 +22,278 @@ pub fn subscription_hub() -> Box<dyn Contract<Empty>> {
         subscription_hub::contract::execute,
         subscription_hub::contract::instantiate,
         subscription_hub::contract::query,
+    )
+    .with_reply(subscription_hub::contract::reply);
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub struct MockCw20InstantiateMsg {
+    pub balances: Vec<(String, Uint128)>,
+}
+
+#[cw_serde]
+pub enum MockCw20ExecuteMsg {
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+    },
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+}
+
+#[cw_serde]
+pub enum MockCw20QueryMsg {
+    Balance { address: String },
+}
+
+#[cw_serde]
+pub struct MockCw20BalanceResponse {
+    pub balance: Uint128,
+}
+
+const MOCK_CW20_BALANCES: Map<String, Uint128> = Map::new("balances");
+
+fn move_cw20_balance(deps: DepsMut, from: &str, to: &str, amount: Uint128) -> StdResult<()> {
+    MOCK_CW20_BALANCES.update(deps.storage, from.to_string(), |balance| {
+        balance
+            .unwrap_or_default()
+            .checked_sub(amount)
+            .map_err(StdError::overflow)
+    })?;
+    MOCK_CW20_BALANCES.update(deps.storage, to.to_string(), |balance| {
+        Ok::<_, StdError>(balance.unwrap_or_default() + amount)
+    })?;
+    Ok(())
+}
+
+pub fn mock_cw20() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps: DepsMut,
+         _env: Env,
+         info: MessageInfo,
+         msg: MockCw20ExecuteMsg|
+         -> StdResult<Response> {
+            match msg {
+                MockCw20ExecuteMsg::Transfer { recipient, amount } => {
+                    move_cw20_balance(deps, info.sender.as_str(), &recipient, amount)?;
+                    Ok(Response::new())
+                }
+                MockCw20ExecuteMsg::Send {
+                    contract,
+                    amount,
+                    msg,
+                } => {
+                    move_cw20_balance(deps, info.sender.as_str(), &contract, amount)?;
+                    Ok(Response::new().add_message(WasmMsg::Execute {
+                        contract_addr: contract,
+                        msg: to_binary(&ExecuteMsg::Receive(Cw20ReceiveMsg {
+                            sender: info.sender.to_string(),
+                            amount,
+                            msg,
+                        }))?,
+                        funds: vec![],
+                    }))
+                }
+            }
+        },
+        |deps: DepsMut,
+         _env: Env,
+         _info: MessageInfo,
+         msg: MockCw20InstantiateMsg|
+         -> StdResult<Response> {
+            for (address, balance) in msg.balances {
+                MOCK_CW20_BALANCES.save(deps.storage, address, &balance)?;
+            }
+            Ok(Response::new())
+        },
+        |deps: Deps, _env: Env, msg: MockCw20QueryMsg| -> StdResult<Binary> {
+            match msg {
+                MockCw20QueryMsg::Balance { address } => to_binary(&MockCw20BalanceResponse {
+                    balance: MOCK_CW20_BALANCES
+                        .may_load(deps.storage, address)?
+                        .unwrap_or_default(),
+                }),
+            }
+        },
+    );
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub struct MockCw721InstantiateMsg {
+    pub owners: Vec<(String, String)>,
+}
+
+#[cw_serde]
+pub enum MockCw721ExecuteMsg {
+    TransferNft { recipient: String, token_id: String },
+}
+
+#[cw_serde]
+pub enum MockCw721QueryMsg {
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+const MOCK_CW721_OWNERS: Map<String, String> = Map::new("owners");
+
+pub fn mock_cw721() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps: DepsMut,
+         _env: Env,
+         info: MessageInfo,
+         msg: MockCw721ExecuteMsg|
+         -> StdResult<Response> {
+            match msg {
+                MockCw721ExecuteMsg::TransferNft {
+                    recipient,
+                    token_id,
+                } => {
+                    if MOCK_CW721_OWNERS.load(deps.storage, token_id.clone())? != info.sender {
+                        return Err(StdError::generic_err("not the owner"));
+                    }
+                    MOCK_CW721_OWNERS.save(deps.storage, token_id, &recipient)?;
+                    Ok(Response::new())
+                }
+            }
+        },
+        |deps: DepsMut,
+         _env: Env,
+         _info: MessageInfo,
+         msg: MockCw721InstantiateMsg|
+         -> StdResult<Response> {
+            for (token_id, owner) in msg.owners {
+                MOCK_CW721_OWNERS.save(deps.storage, token_id, &owner)?;
+            }
+            Ok(Response::new())
+        },
+        |deps: Deps, _env: Env, msg: MockCw721QueryMsg| -> StdResult<Binary> {
+            match msg {
+                MockCw721QueryMsg::Tokens { owner, limit, .. } => {
+                    let tokens = MOCK_CW721_OWNERS
+                        .range(deps.storage, None, None, Order::Ascending)
+                        .filter(
+                            |item| matches!(item, Ok((_, token_owner)) if *token_owner == owner),
+                        )
+                        .take(limit.unwrap_or(10) as usize)
+                        .map(|item| item.map(|(token_id, _)| token_id))
+                        .collect::<StdResult<Vec<String>>>()?;
+                    to_binary(&TokensResponse { tokens })
+                }
+            }
+        },
+    );
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub enum MockOracleExecuteMsg {
+    SetPrice {
+        asset: AssetInfo,
+        currency: String,
+        price: Decimal,
+    },
+}
+
+const MOCK_ORACLE_PRICES: Map<(String, String), OraclePriceResponse> = Map::new("prices");
+
+pub fn mock_oracle() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps: DepsMut,
+         env: Env,
+         _info: MessageInfo,
+         msg: MockOracleExecuteMsg|
+         -> StdResult<Response> {
+            match msg {
+                MockOracleExecuteMsg::SetPrice {
+                    asset,
+                    currency,
+                    price,
+                } => {
+                    MOCK_ORACLE_PRICES.save(
+                        deps.storage,
+                        (asset.key(), currency),
+                        &OraclePriceResponse {
+                            price,
+                            updated_at: env.block.time,
+                        },
+                    )?;
+                    Ok(Response::new())
+                }
+            }
+        },
+        |_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |deps: Deps, _env: Env, msg: OracleQueryMsg| -> StdResult<Binary> {
+            match msg {
+                OracleQueryMsg::Price { asset, currency } => {
+                    to_binary(&MOCK_ORACLE_PRICES.load(deps.storage, (asset.key(), currency))?)
+                }
+            }
+        },
+    );
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub struct MockHookInstantiateMsg {
+    pub fail: bool,
+}
+
+#[cw_serde]
+pub enum MockHookExecuteMsg {
+    SubscriptionHook(SubscriptionHookMsg),
+}
+
+#[cw_serde]
+pub enum MockHookQueryMsg {
+    Received {},
+}
+
+const MOCK_HOOK_FAIL: Item<bool> = Item::new("fail");
+const MOCK_HOOK_RECEIVED: Item<Vec<SubscriptionHookMsg>> = Item::new("received");
+
+pub fn mock_hook() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps: DepsMut,
+         _env: Env,
+         _info: MessageInfo,
+         msg: MockHookExecuteMsg|
+         -> StdResult<Response> {
+            match msg {
+                MockHookExecuteMsg::SubscriptionHook(msg) => {
+                    if MOCK_HOOK_FAIL.load(deps.storage)? {
+                        return Err(StdError::generic_err("hook failed"));
+                    }
+                    let mut received = MOCK_HOOK_RECEIVED.load(deps.storage)?;
+                    received.push(msg);
+                    MOCK_HOOK_RECEIVED.save(deps.storage, &received)?;
+                    Ok(Response::new())
+                }
+            }
+        },
+        |deps: DepsMut,
+         _env: Env,
+         _info: MessageInfo,
+         msg: MockHookInstantiateMsg|
+         -> StdResult<Response> {
+            MOCK_HOOK_FAIL.save(deps.storage, &msg.fail)?;
+            MOCK_HOOK_RECEIVED.save(deps.storage, &vec![])?;
+            Ok(Response::new())
+        },
+        |deps: Deps, _env: Env, msg: MockHookQueryMsg| -> StdResult<Binary> {
+            match msg {
+                MockHookQueryMsg::Received {} => to_binary(&MOCK_HOOK_RECEIVED.load(deps.storage)?),
+            }
+        },
     );
     Box::new(contract)
 }
@@ -113,7 +397,11 @@ pub fn proper_instantiate(app: &mut App, admin: &str) -> Addr {
     app.instantiate_contract(
         code_id,
         Addr::unchecked(admin),
-        &InstantiateMsg {},
+        &InstantiateMsg {
+            oracle: None,
+            oracle_max_staleness: None,
+            limits: None,
+        },
         &vec![],
         "CosmWasm Subscription Hub",
         None,
@@ -121,6 +409,125 @@ pub fn proper_instantiate(app: &mut App, admin: &str) -> Addr {
     .unwrap()
 }
 
+pub fn instantiate_mock_cw20(app: &mut App, balances: Vec<(&str, u128)>) -> Addr {
+    let code_id = app.store_code(mock_cw20());
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(ADMIN),
+        &MockCw20InstantiateMsg {
+            balances: balances
+                .into_iter()
+                .map(|(address, balance)| (address.to_string(), Uint128::new(balance)))
+                .collect(),
+        },
+        &[],
+        "Mock CW20",
+        None,
+    )
+    .unwrap()
+}
+
+pub fn instantiate_mock_cw721(app: &mut App, owners: Vec<(&str, &str)>) -> Addr {
+    let code_id = app.store_code(mock_cw721());
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(ADMIN),
+        &MockCw721InstantiateMsg {
+            owners: owners
+                .into_iter()
+                .map(|(token_id, owner)| (token_id.to_string(), owner.to_string()))
+                .collect(),
+        },
+        &[],
+        "Mock CW721",
+        None,
+    )
+    .unwrap()
+}
+
+pub fn instantiate_mock_oracle(app: &mut App) -> Addr {
+    let code_id = app.store_code(mock_oracle());
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(ADMIN),
+        &Empty {},
+        &[],
+        "Mock Oracle",
+        None,
+    )
+    .unwrap()
+}
+
+pub fn instantiate_mock_hook(app: &mut App, fail: bool) -> Addr {
+    let code_id = app.store_code(mock_hook());
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(ADMIN),
+        &MockHookInstantiateMsg { fail },
+        &[],
+        "Mock Hook",
+        None,
+    )
+    .unwrap()
+}
+
+pub fn query_hook_messages(app: &App, hook: &Addr) -> Vec<SubscriptionHookMsg> {
+    app.wrap()
+        .query_wasm_smart(hook, &MockHookQueryMsg::Received {})
+        .unwrap()
+}
+
+pub fn set_oracle_price(app: &mut App, oracle: &Addr, asset: AssetInfo, price: Decimal) {
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        oracle.clone(),
+        &MockOracleExecuteMsg::SetPrice {
+            asset,
+            currency: "USD".to_string(),
+            price,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+pub fn query_cw20_balance(app: &App, cw20: &Addr, address: &str) -> Uint128 {
+    let res: MockCw20BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            cw20,
+            &MockCw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    res.balance
+}
+
+pub fn query_native_balance(app: &App, address: &str) -> Uint128 {
+    app.wrap().query_balance(address, DENOM).unwrap().amount
+}
+
+pub fn query_credits(app: &App, subscription_hub: &Addr, user: &str) -> Vec<Asset> {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Credits {
+                user_address: user.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+pub fn native_price(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::Native {
+            denom: DENOM.to_string(),
+        },
+        amount: Uint128::new(amount),
+    }
+}
+
 pub fn create_organization(app: &mut App, subscription_hub: &Addr, owner: &str) {
     app.execute_contract(
         Addr::unchecked(owner),
@@ -128,6 +535,7 @@ pub fn create_organization(app: &mut App, subscription_hub: &Addr, owner: &str)
         &ExecuteMsg::CreateOrganization {
             name: "Test Organization".to_string(),
             description: "Test organization is the best".to_string(),
+            handle: None,
             website: None,
             metadata: None,
         },
@@ -150,7 +558,10 @@ pub fn create_subscription_plan(
             organization_id,
             name: "Test Plan".to_string(),
             description: "Test plan is the best".to_string(),
-            price: Uint128::new(10_000),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
             duration: 1,
             duration_unit: DurationUnit::Month,
             features: Some(vec![
@@ -160,18 +571,64 @@ pub fn create_subscription_plan(
             metadata: None,
             cancelable,
             refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
         },
         &vec![],
     )
     .unwrap();
 }
 
+pub fn create_transferable_subscription_plan(
+    app: &mut App,
+    subscription_hub: &Addr,
+    owner: &str,
+    organization_id: u32,
+) {
+    app.execute_contract(
+        Addr::unchecked(owner),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id,
+            name: "Transferable Plan".to_string(),
+            description: "Transferable plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: true,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
 pub fn subscribe_plan(app: &mut App, subscription_hub: &Addr, subscriber: &str, plan_id: u64) {
     app.execute_contract(
         Addr::unchecked(subscriber),
         subscription_hub.clone(),
-        &ExecuteMsg::SubscribePlan { plan_id },
-        &vec![],
+        &ExecuteMsg::SubscribePlan {
+            plan_id,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
     )
     .unwrap();
 }
@@ -0,0 +1,499 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::Asset,
+    msg::{ExecuteMsg, QueryMsg, SubscriptionHookMsg, SubscriptionResponse},
+    state::{DurationUnit, Hook, HookFailurePolicy, PlanKind},
+    ContractError,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+fn add_hook(
+    app: &mut App,
+    subscription_hub: &Addr,
+    hook: &Addr,
+    on_failure: HookFailurePolicy,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::AddHook {
+            organization_id: 1,
+            contract: hook.to_string(),
+            on_failure,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn create_plan(app: &mut App, subscription_hub: &Addr, kind: PlanKind, prices: Vec<Asset>) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Other Plan".to_string(),
+            description: "Other plan is the best".to_string(),
+            prices,
+            reference_price: None,
+            kind: Some(kind),
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn subscribe(app: &mut App, subscription_hub: &Addr, subscriber: &str, plan_id: u64, amount: u128) {
+    let funds = if amount > 0 {
+        coins(amount, DENOM)
+    } else {
+        vec![]
+    };
+    app.execute_contract(
+        Addr::unchecked(subscriber),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &funds,
+    )
+    .unwrap();
+}
+
+fn process_subscription(app: &mut App, subscription_hub: &Addr, subscription_id: u64) {
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hook = instantiate_mock_hook(&mut app, false);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Revert,
+    )
+    .unwrap();
+
+    let res: Vec<Hook> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Hooks { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![Hook {
+            contract: hook.clone(),
+            on_failure: HookFailurePolicy::Revert,
+        }]
+    );
+
+    // Subscribe, renew and cancel
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    let expiration = app.block_info().time.plus_seconds(MONTH);
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub,
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_hook_messages(&app, &hook),
+        vec![
+            SubscriptionHookMsg::Created {
+                subscription_id: 1,
+                plan_id: 1,
+                subscriber: Addr::unchecked(USER),
+                expiration,
+            },
+            SubscriptionHookMsg::Renewed {
+                subscription_id: 1,
+                plan_id: 1,
+                subscriber: Addr::unchecked(USER),
+                expiration: expiration.plus_seconds(MONTH),
+            },
+            SubscriptionHookMsg::Canceled {
+                subscription_id: 1,
+                plan_id: 1,
+                subscriber: Addr::unchecked(USER),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_expired_subscription() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hook = instantiate_mock_hook(&mut app, false);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Revert,
+    )
+    .unwrap();
+
+    // Processing the subscription once it ran out notifies the hook once
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH + 1));
+    for _ in 0..2 {
+        app.execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub.clone(),
+            &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+            &[],
+        )
+        .unwrap();
+    }
+
+    assert_eq!(
+        query_hook_messages(&app, &hook),
+        vec![SubscriptionHookMsg::Expired {
+            subscription_id: 1,
+            plan_id: 1,
+            subscriber: Addr::unchecked(USER),
+        }]
+    );
+}
+
+#[test]
+fn test_expired_plan_kinds() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hook = instantiate_mock_hook(&mut app, false);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_plan(
+        &mut app,
+        &subscription_hub,
+        PlanKind::Installments {
+            count: 1,
+            grace_period: 0,
+        },
+        vec![native_price(10_000)],
+    );
+    create_plan(
+        &mut app,
+        &subscription_hub,
+        PlanKind::AccessPass { uses: 1 },
+        vec![native_price(5_000)],
+    );
+    subscribe(&mut app, &subscription_hub, USER, 1, 10_000);
+    subscribe(&mut app, &subscription_hub, USER2, 2, 5_000);
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Revert,
+    )
+    .unwrap();
+
+    // A used up access pass expires
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::ReportUsage {
+            subscription_id: 2,
+            units: 1,
+        },
+        &[],
+    )
+    .unwrap();
+    process_subscription(&mut app, &subscription_hub, 2);
+
+    // A fully paid installment plan expires at the end of its duration
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH + 1));
+    process_subscription(&mut app, &subscription_hub, 1);
+
+    assert_eq!(
+        query_hook_messages(&app, &hook),
+        vec![
+            SubscriptionHookMsg::Expired {
+                subscription_id: 2,
+                plan_id: 2,
+                subscriber: Addr::unchecked(USER2),
+            },
+            SubscriptionHookMsg::Expired {
+                subscription_id: 1,
+                plan_id: 1,
+                subscriber: Addr::unchecked(USER),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_free_renewal() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hook = instantiate_mock_hook(&mut app, false);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_plan(&mut app, &subscription_hub, PlanKind::Recurring, vec![]);
+    subscribe(&mut app, &subscription_hub, USER, 1, 0);
+    let expiration = app.block_info().time.plus_seconds(MONTH);
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Revert,
+    )
+    .unwrap();
+
+    // Free plans are renewed by the crank without a prepaid balance
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH));
+    process_subscription(&mut app, &subscription_hub, 1);
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.expiration, expiration.plus_seconds(MONTH));
+    assert_eq!(
+        query_hook_messages(&app, &hook),
+        vec![SubscriptionHookMsg::Renewed {
+            subscription_id: 1,
+            plan_id: 1,
+            subscriber: Addr::unchecked(USER),
+            expiration: expiration.plus_seconds(MONTH),
+        }]
+    );
+}
+
+#[test]
+fn test_plan_changed() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hook = instantiate_mock_hook(&mut app, false);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Revert,
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::ChangePlan {
+            subscription_id: 1,
+            plan_id: 2,
+            max_price: None,
+            proof: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_hook_messages(&app, &hook),
+        vec![SubscriptionHookMsg::PlanChanged {
+            subscription_id: 1,
+            old_plan_id: 1,
+            new_plan_id: 2,
+            subscriber: Addr::unchecked(USER),
+        }]
+    );
+}
+
+#[test]
+fn test_failing_hook() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hook = instantiate_mock_hook(&mut app, true);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    // A failing hook reverts the subscription
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Revert,
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap_err();
+
+    // An ignored failing hook is logged and the subscription goes through
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::RemoveHook {
+            organization_id: 1,
+            contract: hook.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Ignore,
+    )
+    .unwrap();
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap();
+    assert!(res
+        .events
+        .iter()
+        .any(|event| event.ty == "wasm-hook_failed"));
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(res);
+}
+
+#[test]
+fn test_invalid_hooks() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let hook = instantiate_mock_hook(&mut app, false);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::AddHook {
+                organization_id: 1,
+                contract: hook.to_string(),
+                on_failure: HookFailurePolicy::Ignore,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized {}.to_string()
+    );
+
+    add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Ignore,
+    )
+    .unwrap();
+    let err = add_hook(
+        &mut app,
+        &subscription_hub,
+        &hook,
+        HookFailurePolicy::Revert,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HookAlreadyExists {}.to_string());
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ORGANIZATION),
+            subscription_hub,
+            &ExecuteMsg::RemoveHook {
+                organization_id: 1,
+                contract: USER3.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::HookNotFound {}.to_string()
+    );
+}
@@ -0,0 +1,257 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::Asset,
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse, UsageResponse},
+    state::{DurationUnit, PlanKind, SubscriptionStatus},
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn create_hybrid_subscription_plan(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Hybrid Plan".to_string(),
+            description: "Hybrid plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: Some(PlanKind::Hybrid {
+                included_units: 1_000,
+                overage_prices: vec![native_price(10)],
+            }),
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn report_usage(app: &mut App, subscription_hub: &Addr, units: u64) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::ReportUsage {
+            subscription_id: 1,
+            units,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn query_usage(app: &App, subscription_hub: &Addr) -> UsageResponse {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Usage {
+                subscription_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_hybrid_subscription_plan(&mut app, &subscription_hub);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::TopUp { subscription_id: 1 },
+        &coins(5_000, DENOM),
+    )
+    .unwrap();
+
+    // The overage is not charged while the period is open
+    report_usage(&mut app, &subscription_hub, 1_200);
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+    let res = query_usage(&app, &subscription_hub);
+    assert_eq!(res.balance, Some(native_price(5_000)));
+    assert!(!res.periods[0].settled);
+
+    // The overage is charged from the balance once the period is closed
+    app.update_block(|block| block.time = block.time.plus_seconds(30 * DAY));
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+    let res = query_usage(&app, &subscription_hub);
+    assert_eq!(res.current_period, 1);
+    assert_eq!(res.balance, Some(native_price(3_000)));
+    assert!(res.periods[0].settled);
+    assert_eq!(res.periods[0].cost, Uint128::new(2_000));
+
+    // The included units reset in the new period
+    report_usage(&mut app, &subscription_hub, 500);
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(22_000)]);
+
+    // Once expired the last period is closed and the unused balance is credited
+    app.update_block(|block| block.time = block.time.plus_seconds(31 * DAY));
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+    let res = query_usage(&app, &subscription_hub);
+    assert_eq!(res.balance, Some(native_price(0)));
+    assert!(res.periods[1].settled);
+    assert_eq!(res.periods[1].cost, Uint128::zero());
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(975_000));
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(3_000)]
+    );
+}
+
+#[test]
+fn test_billing_anchor() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_hybrid_subscription_plan(&mut app, &subscription_hub);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(15 * DAY));
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    report_usage(&mut app, &subscription_hub, 800);
+
+    // The period follows the subscription start instead of the calendar
+    app.update_block(|block| block.time = block.time.plus_seconds(20 * DAY));
+    report_usage(&mut app, &subscription_hub, 100);
+
+    let res = query_usage(&app, &subscription_hub);
+    assert_eq!(res.current_period, 0);
+    assert_eq!(res.periods.len(), 1);
+    assert_eq!(res.periods[0].units, 900);
+    assert_eq!(
+        res.periods[0].start,
+        app.block_info().time.minus_seconds(20 * DAY)
+    );
+}
+
+#[test]
+fn test_insufficient_balance() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_hybrid_subscription_plan(&mut app, &subscription_hub);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::TopUp { subscription_id: 1 },
+        &coins(400, DENOM),
+    )
+    .unwrap();
+    report_usage(&mut app, &subscription_hub, 1_100);
+
+    // The balance pays what it can and the rest of the overage is recorded as unpaid
+    app.update_block(|block| block.time = block.time.plus_seconds(30 * DAY));
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+    let res = query_usage(&app, &subscription_hub);
+    assert_eq!(res.balance, Some(native_price(0)));
+    assert!(res.periods[0].settled);
+    assert_eq!(res.periods[0].cost, Uint128::new(400));
+    assert_eq!(res.periods[0].unpaid, Uint128::new(600));
+
+    // The unpaid overage does not keep the subscription from lapsing
+    app.update_block(|block| block.time = block.time.plus_seconds(30 * DAY));
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.status, SubscriptionStatus::Lapsed);
+}
+
+#[test]
+fn test_cancel_with_insufficient_balance() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_hybrid_subscription_plan(&mut app, &subscription_hub);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    report_usage(&mut app, &subscription_hub, 1_100);
+
+    // Canceling charges the overage the balance covers instead of failing
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    let res = query_usage(&app, &subscription_hub);
+    assert_eq!(res.periods[0].cost, Uint128::zero());
+    assert_eq!(res.periods[0].unpaid, Uint128::new(1_000));
+}
@@ -0,0 +1,213 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::Asset,
+    msg::{ExecuteMsg, InstallmentsResponse, QueryMsg},
+    state::{DurationUnit, PlanKind, SubscriptionStatus},
+    ContractError,
+};
+
+const INTERVAL: u64 = 365 * 24 * 60 * 60 / 4;
+const GRACE_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+fn create_installment_subscription_plan(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Annual Plan".to_string(),
+            description: "Annual plan is the best".to_string(),
+            prices: vec![native_price(12_000)],
+            reference_price: None,
+            kind: Some(PlanKind::Installments {
+                count: 4,
+                grace_period: GRACE_PERIOD,
+            }),
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Year,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn subscribe_installment_plan(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(3_000, DENOM),
+    )
+    .unwrap();
+}
+
+fn process_subscription(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+}
+
+fn query_installments(app: &App, subscription_hub: &Addr) -> InstallmentsResponse {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Installments { subscription_id: 1 },
+        )
+        .unwrap()
+}
+
+fn is_subscribed(app: &App, subscription_hub: &Addr) -> bool {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_installment_subscription_plan(&mut app, &subscription_hub);
+    subscribe_installment_plan(&mut app, &subscription_hub);
+
+    let res = query_installments(&app, &subscription_hub);
+    assert_eq!(res.outstanding, native_price(9_000));
+    assert_eq!(res.next_installment, Some(native_price(3_000)));
+    assert_eq!(
+        res.next_due,
+        Some(app.block_info().time.plus_seconds(INTERVAL))
+    );
+
+    // Pay the second installment with a pay message
+    app.update_block(|block| block.time = block.time.plus_seconds(INTERVAL));
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::PayInstallment { subscription_id: 1 },
+        &coins(3_000, DENOM),
+    )
+    .unwrap();
+
+    // Pay the remaining installments from escrow when they are due
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::TopUp { subscription_id: 1 },
+        &coins(6_000, DENOM),
+    )
+    .unwrap();
+    process_subscription(&mut app, &subscription_hub);
+    assert_eq!(
+        query_installments(&app, &subscription_hub).outstanding,
+        native_price(6_000)
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(INTERVAL));
+    process_subscription(&mut app, &subscription_hub);
+    app.update_block(|block| block.time = block.time.plus_seconds(INTERVAL));
+    process_subscription(&mut app, &subscription_hub);
+
+    let res = query_installments(&app, &subscription_hub);
+    assert_eq!(res.outstanding, native_price(0));
+    assert_eq!(res.next_installment, None);
+    assert!(is_subscribed(&app, &subscription_hub));
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(12_000)]);
+}
+
+#[test]
+fn test_missed_installment() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_installment_subscription_plan(&mut app, &subscription_hub);
+    subscribe_installment_plan(&mut app, &subscription_hub);
+
+    // Access continues during the grace period
+    app.update_block(|block| block.time = block.time.plus_seconds(INTERVAL + GRACE_PERIOD));
+    assert!(is_subscribed(&app, &subscription_hub));
+
+    // Access is suspended once the grace period is over
+    app.update_block(|block| block.time = block.time.plus_seconds(1));
+    assert!(!is_subscribed(&app, &subscription_hub));
+    process_subscription(&mut app, &subscription_hub);
+    let res = query_installments(&app, &subscription_hub);
+    assert_eq!(res.status, SubscriptionStatus::Suspended);
+    assert!(res.overdue);
+
+    // Paying the missed installment restores access
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::PayInstallment { subscription_id: 1 },
+        &coins(3_000, DENOM),
+    )
+    .unwrap();
+    let res = query_installments(&app, &subscription_hub);
+    assert_eq!(res.status, SubscriptionStatus::Active);
+    assert!(is_subscribed(&app, &subscription_hub));
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(994_000));
+}
+
+#[test]
+fn test_invalid_installment_payment() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_installment_subscription_plan(&mut app, &subscription_hub);
+    subscribe_installment_plan(&mut app, &subscription_hub);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::PayInstallment { subscription_id: 1 },
+            &coins(2_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::InvalidPayment {}.to_string()
+    );
+}
@@ -0,0 +1,224 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::Asset,
+    msg::{ExecuteMsg, QueryMsg, UsageResponse},
+    state::{DurationUnit, PlanKind},
+    ContractError,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+fn create_metered_subscription_plan(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Metered Plan".to_string(),
+            description: "Metered plan is the best".to_string(),
+            prices: vec![native_price(10)],
+            reference_price: None,
+            kind: Some(PlanKind::Metered {
+                usage_cap: Some(1_000),
+            }),
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn subscribe_metered_plan(app: &mut App, subscription_hub: &Addr, deposit: u128) {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(deposit, DENOM),
+    )
+    .unwrap();
+}
+
+fn report_usage(
+    app: &mut App,
+    subscription_hub: &Addr,
+    reporter: &str,
+    units: u64,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(reporter),
+        subscription_hub.clone(),
+        &ExecuteMsg::ReportUsage {
+            subscription_id: 1,
+            units,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_metered_subscription_plan(&mut app, &subscription_hub);
+    subscribe_metered_plan(&mut app, &subscription_hub, 5_000);
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(res);
+
+    // The organization reports usage directly
+    report_usage(&mut app, &subscription_hub, ORGANIZATION, 100).unwrap();
+
+    // An authorized reporter reports usage on behalf of the organization
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::SetUsageReporters {
+            organization_id: 1,
+            reporters: vec![USER3.to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+    report_usage(&mut app, &subscription_hub, USER3, 50).unwrap();
+
+    // Usage in the next period is recorded separately
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH));
+    report_usage(&mut app, &subscription_hub, USER3, 20).unwrap();
+
+    let res: UsageResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Usage {
+                subscription_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.current_period, 1);
+    assert_eq!(res.balance, Some(native_price(3_300)));
+    assert_eq!(res.periods.len(), 2);
+    assert_eq!(res.periods[0].units, 150);
+    assert_eq!(res.periods[0].cost, Uint128::new(1_500));
+    assert_eq!(res.periods[1].units, 20);
+    assert_eq!(res.periods[1].start, res.periods[0].end);
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(1_700)]);
+
+    // Top up the balance and cancel, the unused balance is credited
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::TopUp { subscription_id: 1 },
+        &coins(1_000, DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(994_000));
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(4_300)]
+    );
+}
+
+#[test]
+fn test_unauthorized_reporter() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_metered_subscription_plan(&mut app, &subscription_hub);
+    subscribe_metered_plan(&mut app, &subscription_hub, 5_000);
+
+    let err = report_usage(&mut app, &subscription_hub, USER, 1).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+}
+
+#[test]
+fn test_usage_cap_exceeded() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_metered_subscription_plan(&mut app, &subscription_hub);
+    subscribe_metered_plan(&mut app, &subscription_hub, 50_000);
+
+    report_usage(&mut app, &subscription_hub, ORGANIZATION, 600).unwrap();
+    let err = report_usage(&mut app, &subscription_hub, ORGANIZATION, 401).unwrap_err();
+    assert_eq!(err, ContractError::UsageCapExceeded {}.to_string());
+}
+
+#[test]
+fn test_insufficient_balance() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_metered_subscription_plan(&mut app, &subscription_hub);
+    subscribe_metered_plan(&mut app, &subscription_hub, 500);
+
+    let err = report_usage(&mut app, &subscription_hub, ORGANIZATION, 51).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientBalance {}.to_string());
+}
+
+#[test]
+fn test_report_usage_of_recurring_plan() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let err = report_usage(&mut app, &subscription_hub, ORGANIZATION, 1).unwrap_err();
+    assert_eq!(err, ContractError::NotMetered {}.to_string());
+}
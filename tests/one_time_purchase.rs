@@ -0,0 +1,218 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
+    state::{DurationUnit, PlanKind},
+    ContractError,
+};
+
+fn create_one_time_subscription_plan(
+    app: &mut App,
+    subscription_hub: &Addr,
+    kind: PlanKind,
+    price: u128,
+) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "One Time Plan".to_string(),
+            description: "One time plan is the best".to_string(),
+            prices: vec![native_price(price)],
+            reference_price: None,
+            kind: Some(kind),
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn is_subscribed(app: &App, subscription_hub: &Addr) -> bool {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap()
+}
+
+fn use_access_pass(app: &mut App, subscription_hub: &Addr, uses: u64) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::ReportUsage {
+            subscription_id: 1,
+            units: uses,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+#[test]
+fn test_lifetime_plan() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_one_time_subscription_plan(&mut app, &subscription_hub, PlanKind::Lifetime, 10_000);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // The subscription never expires
+    app.update_block(|block| block.time = block.time.plus_seconds(10 * 365 * 24 * 60 * 60));
+    assert!(is_subscribed(&app, &subscription_hub));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::RenewSubscription {
+                subscription_id: 1,
+                max_price: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::NotRenewable {}.to_string()
+    );
+
+    // Terminating a lifetime subscription does not refund it
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert!(!is_subscribed(&app, &subscription_hub));
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(990_000));
+}
+
+#[test]
+fn test_access_pass() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_one_time_subscription_plan(
+        &mut app,
+        &subscription_hub,
+        PlanKind::AccessPass { uses: 3 },
+        9_000,
+    );
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(9_000, DENOM),
+    )
+    .unwrap();
+
+    use_access_pass(&mut app, &subscription_hub, 2).unwrap();
+
+    let err = use_access_pass(&mut app, &subscription_hub, 2).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientUses {}.to_string());
+
+    // The pass is no longer active once every use is consumed
+    use_access_pass(&mut app, &subscription_hub, 1).unwrap();
+    assert!(!is_subscribed(&app, &subscription_hub));
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.remaining_uses, Some(0));
+
+    // A new pass can be purchased through the same subscribe flow
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(9_000, DENOM),
+    )
+    .unwrap();
+    assert!(is_subscribed(&app, &subscription_hub));
+}
+
+#[test]
+fn test_access_pass_refund() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_one_time_subscription_plan(
+        &mut app,
+        &subscription_hub,
+        PlanKind::AccessPass { uses: 3 },
+        9_000,
+    );
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(9_000, DENOM),
+    )
+    .unwrap();
+
+    use_access_pass(&mut app, &subscription_hub, 1).unwrap();
+
+    // The remaining uses are credited
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(991_000));
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(6_000)]
+    );
+}
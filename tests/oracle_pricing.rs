@@ -0,0 +1,212 @@
+pub mod helpers;
+use helpers::*;
+
+use std::str::FromStr;
+
+use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::AssetInfo,
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
+    state::{Config, DurationUnit, ReferencePrice},
+    ContractError,
+};
+
+fn native_asset() -> AssetInfo {
+    AssetInfo::Native {
+        denom: DENOM.to_string(),
+    }
+}
+
+fn create_usd_subscription_plan(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "USD Plan".to_string(),
+            description: "USD plan is the best".to_string(),
+            prices: vec![],
+            reference_price: Some(ReferencePrice {
+                currency: "USD".to_string(),
+                amount: Decimal::from_str("10").unwrap(),
+                assets: vec![native_asset()],
+            }),
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn setup(app: &mut App) -> (Addr, Addr) {
+    let subscription_hub = proper_instantiate(app, ADMIN);
+    let oracle = instantiate_mock_oracle(app);
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        subscription_hub.clone(),
+        &ExecuteMsg::UpdateConfig {
+            admin: None,
+            oracle: Some(oracle.to_string()),
+            oracle_max_staleness: None,
+            limits: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // 1 uconst = 0.001 USD, so the 10 USD plan costs 10_000 uconst
+    set_oracle_price(
+        app,
+        &oracle,
+        native_asset(),
+        Decimal::from_str("0.001").unwrap(),
+    );
+
+    create_organization(app, &subscription_hub, ORGANIZATION);
+    create_usd_subscription_plan(app, &subscription_hub);
+
+    (subscription_hub, oracle)
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let (subscription_hub, oracle) = setup(&mut app);
+
+    let config: Config = app
+        .wrap()
+        .query_wasm_smart(subscription_hub.clone(), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.oracle, Some(oracle));
+
+    // The excess of the payment is returned to the payer
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: Some(Uint128::new(12_000)),
+            proof: None,
+            referrer: None,
+        },
+        &coins(12_000, DENOM),
+    )
+    .unwrap();
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(990_000));
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.price, Some(native_price(10_000)));
+}
+
+#[test]
+fn test_slippage_exceeded() {
+    let mut app = mock_app();
+    let (subscription_hub, oracle) = setup(&mut app);
+
+    // The asset loses value between quoting and paying
+    set_oracle_price(
+        &mut app,
+        &oracle,
+        native_asset(),
+        Decimal::from_str("0.0008").unwrap(),
+    );
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: None,
+                max_price: Some(Uint128::new(11_000)),
+                proof: None,
+                referrer: None,
+            },
+            &coins(12_500, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::SlippageExceeded {}.to_string()
+    );
+}
+
+#[test]
+fn test_stale_price() {
+    let mut app = mock_app();
+    let (subscription_hub, _) = setup(&mut app);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3_601));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::StalePrice {}.to_string()
+    );
+}
+
+#[test]
+fn test_oracle_not_configured() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_usd_subscription_plan(&mut app, &subscription_hub);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::OracleNotConfigured {}.to_string()
+    );
+}
@@ -0,0 +1,137 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::Addr;
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::{ExecuteMsg, OrganizationResponse, QueryMsg},
+    ContractError,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn create_organization_with_handle(
+    app: &mut App,
+    subscription_hub: &Addr,
+    owner: &str,
+    handle: &str,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(owner),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateOrganization {
+            name: "Test Organization".to_string(),
+            description: "Test organization is the best".to_string(),
+            handle: Some(handle.to_string()),
+            website: None,
+            metadata: None,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn set_handle(
+    app: &mut App,
+    subscription_hub: &Addr,
+    owner: &str,
+    organization_id: u32,
+    handle: Option<&str>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(owner),
+        subscription_hub.clone(),
+        &ExecuteMsg::SetOrganizationHandle {
+            organization_id,
+            handle: handle.map(str::to_string),
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn query_by_handle(app: &App, subscription_hub: &Addr, handle: &str) -> Option<u32> {
+    app.wrap()
+        .query_wasm_smart::<OrganizationResponse>(
+            subscription_hub,
+            &QueryMsg::OrganizationByHandle {
+                handle: handle.to_string(),
+            },
+        )
+        .ok()
+        .map(|res| res.id)
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    // Handles are normalized
+    create_organization_with_handle(&mut app, &subscription_hub, ORGANIZATION, " My-Org ").unwrap();
+    assert_eq!(query_by_handle(&app, &subscription_hub, "MY-ORG"), Some(1));
+
+    let res: OrganizationResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Organization { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.handle, Some("my-org".to_string()));
+
+    let err =
+        create_organization_with_handle(&mut app, &subscription_hub, USER, "my-org").unwrap_err();
+    assert_eq!(err, ContractError::HandleTaken {}.to_string());
+
+    for handle in ["ab", "my org", "-my-org", "my_org"] {
+        let err =
+            create_organization_with_handle(&mut app, &subscription_hub, USER, handle).unwrap_err();
+        assert_eq!(err, ContractError::InvalidHandle {}.to_string());
+    }
+}
+
+#[test]
+fn test_change_handle() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization_with_handle(&mut app, &subscription_hub, ORGANIZATION, "my-org").unwrap();
+    create_organization(&mut app, &subscription_hub, USER);
+
+    let err = set_handle(&mut app, &subscription_hub, USER, 1, Some("new-org")).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    // The handle can only be changed after the cooldown
+    let err = set_handle(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        1,
+        Some("new-org"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HandleCooldown {}.to_string());
+
+    app.update_block(|block| block.time = block.time.plus_seconds(30 * DAY));
+    set_handle(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        1,
+        Some("new-org"),
+    )
+    .unwrap();
+    assert_eq!(query_by_handle(&app, &subscription_hub, "new-org"), Some(1));
+    assert_eq!(query_by_handle(&app, &subscription_hub, "my-org"), None);
+
+    // The released handle stays reserved for a while
+    let err = set_handle(&mut app, &subscription_hub, USER, 2, Some("my-org")).unwrap_err();
+    assert_eq!(err, ContractError::HandleReserved {}.to_string());
+
+    app.update_block(|block| block.time = block.time.plus_seconds(90 * DAY));
+    set_handle(&mut app, &subscription_hub, USER, 2, Some("my-org")).unwrap();
+    assert_eq!(query_by_handle(&app, &subscription_hub, "my-org"), Some(2));
+}
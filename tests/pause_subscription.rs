@@ -0,0 +1,244 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
+    state::{DurationUnit, SubscriptionStatus},
+    ContractError,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn create_pausable_subscription_plan(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Seasonal Plan".to_string(),
+            description: "Seasonal plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: true,
+            pausable: true,
+            max_pause_duration: Some(60 * DAY),
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn pause_subscription(app: &mut App, subscription_hub: &Addr) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::PauseSubscription { subscription_id: 1 },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn resume_subscription(app: &mut App, subscription_hub: &Addr) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::ResumeSubscription { subscription_id: 1 },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn query_subscription(app: &App, subscription_hub: &Addr) -> SubscriptionResponse {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap()
+}
+
+fn is_subscribed(app: &App, subscription_hub: &Addr) -> bool {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_pausable_subscription_plan(&mut app, &subscription_hub);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // Pause with 20 days remaining
+    app.update_block(|block| block.time = block.time.plus_seconds(10 * DAY));
+    pause_subscription(&mut app, &subscription_hub).unwrap();
+    assert!(!is_subscribed(&app, &subscription_hub));
+    assert_eq!(
+        query_subscription(&app, &subscription_hub).data.status,
+        SubscriptionStatus::Paused
+    );
+
+    let err = pause_subscription(&mut app, &subscription_hub).unwrap_err();
+    assert_eq!(err, ContractError::SubscriptionPaused {}.to_string());
+
+    // The remaining time is restored on resume
+    app.update_block(|block| block.time = block.time.plus_seconds(40 * DAY));
+    resume_subscription(&mut app, &subscription_hub).unwrap();
+    assert!(is_subscribed(&app, &subscription_hub));
+
+    let res = query_subscription(&app, &subscription_hub);
+    assert_eq!(res.data.status, SubscriptionStatus::Active);
+    assert_eq!(
+        res.data.expiration,
+        app.block_info().time.plus_seconds(20 * DAY)
+    );
+
+    let err = resume_subscription(&mut app, &subscription_hub).unwrap_err();
+    assert_eq!(err, ContractError::NotPaused {}.to_string());
+}
+
+#[test]
+fn test_paused_subscription_is_kept() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_pausable_subscription_plan(&mut app, &subscription_hub);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    subscribe_plan(&mut app, &subscription_hub, USER2, 1);
+    pause_subscription(&mut app, &subscription_hub).unwrap();
+
+    // The paused subscription cannot be replaced by a new one
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub.clone(),
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: Some(USER.to_string()),
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::AlreadySubscribed {}.to_string()
+    );
+
+    // Nor by a transferred one
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER2),
+            subscription_hub.clone(),
+            &ExecuteMsg::TransferSubscription {
+                subscription_id: 2,
+                recipient: USER.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::AlreadySubscribed {}.to_string()
+    );
+
+    // The paused time is still there to resume
+    resume_subscription(&mut app, &subscription_hub).unwrap();
+    assert!(is_subscribed(&app, &subscription_hub));
+}
+
+#[test]
+fn test_max_pause_duration() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_pausable_subscription_plan(&mut app, &subscription_hub);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(10 * DAY));
+    pause_subscription(&mut app, &subscription_hub).unwrap();
+    let paused_at = app.block_info().time;
+
+    // The subscription resumes by itself once the maximum pause length is over
+    app.update_block(|block| block.time = block.time.plus_seconds(70 * DAY));
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let res = query_subscription(&app, &subscription_hub);
+    assert_eq!(res.data.status, SubscriptionStatus::Active);
+    assert_eq!(res.data.expiration, paused_at.plus_seconds(80 * DAY));
+    assert!(is_subscribed(&app, &subscription_hub));
+}
+
+#[test]
+fn test_renew_paused_subscription() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_pausable_subscription_plan(&mut app, &subscription_hub);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    pause_subscription(&mut app, &subscription_hub).unwrap();
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::RenewSubscription {
+                subscription_id: 1,
+                max_price: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::SubscriptionPaused {}.to_string()
+    );
+}
+
+#[test]
+fn test_not_pausable() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let err = pause_subscription(&mut app, &subscription_hub).unwrap_err();
+    assert_eq!(err, ContractError::NotPausable {}.to_string());
+}
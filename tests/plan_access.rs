@@ -0,0 +1,232 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use sha2::{Digest, Sha256};
+use subscription_hub::{
+    msg::{ExecuteMsg, QueryMsg},
+    state::PlanAccess,
+    ContractError,
+};
+
+fn set_plan_access(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    plan_id: u64,
+    access: PlanAccess,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::SetPlanAccess { plan_id, access },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn try_subscribe(
+    app: &mut App,
+    subscription_hub: &Addr,
+    subscriber: &str,
+    plan_id: u64,
+    proof: Option<Vec<String>>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(subscriber),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn hash_pair(first: [u8; 32], second: [u8; 32]) -> [u8; 32] {
+    let (first, second) = if first <= second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    Sha256::digest([first, second].concat()).into()
+}
+
+#[test]
+fn test_allowlist() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    let err =
+        set_plan_access(&mut app, &subscription_hub, USER, 1, PlanAccess::Allowlist).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    set_plan_access(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        1,
+        PlanAccess::Allowlist,
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::UpdatePlanAllowlist {
+            plan_id: 1,
+            add: vec![USER.to_string(), USER2.to_string()],
+            remove: vec![USER2.to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: Vec<Addr> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::PlanAllowlist {
+                plan_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res, vec![Addr::unchecked(USER)]);
+
+    try_subscribe(&mut app, &subscription_hub, USER, 1, None).unwrap();
+    let err = try_subscribe(&mut app, &subscription_hub, USER2, 1, None).unwrap_err();
+    assert_eq!(err, ContractError::NotAllowed {}.to_string());
+
+    // Making the plan public again opens it to everyone
+    set_plan_access(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        1,
+        PlanAccess::Public,
+    )
+    .unwrap();
+    try_subscribe(&mut app, &subscription_hub, USER2, 1, None).unwrap();
+}
+
+#[test]
+fn test_merkle_allowlist() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    // Tree of USER and USER2
+    let user_leaf: [u8; 32] = Sha256::digest(USER.as_bytes()).into();
+    let user2_leaf: [u8; 32] = Sha256::digest(USER2.as_bytes()).into();
+    let root = hex::encode(hash_pair(user_leaf, user2_leaf));
+
+    let err = set_plan_access(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        1,
+        PlanAccess::MerkleAllowlist {
+            root: "not a root".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidMerkleRoot {}.to_string());
+
+    set_plan_access(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        1,
+        PlanAccess::MerkleAllowlist { root: root.clone() },
+    )
+    .unwrap();
+    let res: PlanAccess = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::PlanAccess { plan_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, PlanAccess::MerkleAllowlist { root });
+
+    let err = try_subscribe(&mut app, &subscription_hub, USER, 1, None).unwrap_err();
+    assert_eq!(err, ContractError::NotAllowed {}.to_string());
+    let err = try_subscribe(
+        &mut app,
+        &subscription_hub,
+        USER,
+        1,
+        Some(vec!["zz".to_string()]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {}.to_string());
+
+    try_subscribe(
+        &mut app,
+        &subscription_hub,
+        USER,
+        1,
+        Some(vec![hex::encode(user2_leaf)]),
+    )
+    .unwrap();
+
+    // USER3 is not in the tree
+    let err = try_subscribe(
+        &mut app,
+        &subscription_hub,
+        USER3,
+        1,
+        Some(vec![hex::encode(user_leaf)]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotAllowed {}.to_string());
+}
+
+#[test]
+fn test_prerequisite() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    let err = set_plan_access(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        2,
+        PlanAccess::Prerequisite { plan_id: 2 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPrerequisite {}.to_string());
+
+    set_plan_access(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        2,
+        PlanAccess::Prerequisite { plan_id: 1 },
+    )
+    .unwrap();
+
+    let err = try_subscribe(&mut app, &subscription_hub, USER, 2, None).unwrap_err();
+    assert_eq!(err, ContractError::PrerequisiteNotMet {}.to_string());
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    try_subscribe(&mut app, &subscription_hub, USER, 2, None).unwrap();
+}
@@ -0,0 +1,308 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::{ExecuteMsg, PlanCapacityResponse, QueryMsg, WaitlistEntry},
+    state::DurationUnit,
+    ContractError,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+fn create_limited_subscription_plan(
+    app: &mut App,
+    subscription_hub: &Addr,
+    max_subscribers: u32,
+    waitlist: bool,
+) {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Beta Plan".to_string(),
+            description: "Beta plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: true,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: Some(max_subscribers),
+            waitlist,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn try_subscribe(app: &mut App, subscription_hub: &Addr, subscriber: &str) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(subscriber),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn waitlist_action(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> Result<(), String> {
+    app.execute_contract(Addr::unchecked(sender), subscription_hub.clone(), &msg, &[])
+        .map(|_| ())
+        .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn active_subscribers(app: &App, subscription_hub: &Addr) -> u32 {
+    let res: PlanCapacityResponse = app
+        .wrap()
+        .query_wasm_smart(subscription_hub, &QueryMsg::PlanCapacity { plan_id: 1 })
+        .unwrap();
+    res.active_subscribers
+}
+
+fn query_waitlist(app: &App, subscription_hub: &Addr) -> Vec<Addr> {
+    let res: Vec<WaitlistEntry> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Waitlist {
+                plan_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    res.into_iter().map(|entry| entry.user).collect()
+}
+
+#[test]
+fn test_max_subscribers() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_limited_subscription_plan(&mut app, &subscription_hub, 2, false);
+
+    try_subscribe(&mut app, &subscription_hub, USER).unwrap();
+    try_subscribe(&mut app, &subscription_hub, USER2).unwrap();
+    assert_eq!(active_subscribers(&app, &subscription_hub), 2);
+
+    let err = try_subscribe(&mut app, &subscription_hub, USER3).unwrap_err();
+    assert_eq!(err, ContractError::PlanFull {}.to_string());
+
+    // Canceling frees a seat
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(active_subscribers(&app, &subscription_hub), 1);
+    try_subscribe(&mut app, &subscription_hub, USER3).unwrap();
+    assert_eq!(active_subscribers(&app, &subscription_hub), 2);
+}
+
+#[test]
+fn test_expired_subscription_frees_seat() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_limited_subscription_plan(&mut app, &subscription_hub, 1, false);
+    try_subscribe(&mut app, &subscription_hub, USER).unwrap();
+
+    // The seat is freed once the expired subscription is processed
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH + 1));
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(active_subscribers(&app, &subscription_hub), 0);
+    try_subscribe(&mut app, &subscription_hub, USER2).unwrap();
+
+    // The lapsed subscription cannot come back while the plan is full
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::RenewSubscription {
+                subscription_id: 1,
+                max_price: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::PlanFull {}.to_string()
+    );
+}
+
+#[test]
+fn test_transfer_frees_replaced_seat() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_limited_subscription_plan(&mut app, &subscription_hub, 2, false);
+    try_subscribe(&mut app, &subscription_hub, USER).unwrap();
+
+    // The expired subscription keeps its seat until it is replaced
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH + 1));
+    try_subscribe(&mut app, &subscription_hub, USER2).unwrap();
+    assert_eq!(active_subscribers(&app, &subscription_hub), 2);
+
+    // Transferring to the expired subscriber replaces its subscription and frees the seat
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        subscription_hub.clone(),
+        &ExecuteMsg::TransferSubscription {
+            subscription_id: 2,
+            recipient: USER.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(active_subscribers(&app, &subscription_hub), 1);
+    try_subscribe(&mut app, &subscription_hub, USER3).unwrap();
+}
+
+#[test]
+fn test_waitlist() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_limited_subscription_plan(&mut app, &subscription_hub, 1, true);
+    try_subscribe(&mut app, &subscription_hub, USER).unwrap();
+
+    // Users queue in order
+    for user in [USER2, USER3] {
+        waitlist_action(
+            &mut app,
+            &subscription_hub,
+            user,
+            ExecuteMsg::JoinWaitlist { plan_id: 1 },
+        )
+        .unwrap();
+    }
+    let err = waitlist_action(
+        &mut app,
+        &subscription_hub,
+        USER2,
+        ExecuteMsg::JoinWaitlist { plan_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AlreadyWaitlisted {}.to_string());
+    assert_eq!(
+        query_waitlist(&app, &subscription_hub),
+        vec![Addr::unchecked(USER2), Addr::unchecked(USER3)]
+    );
+
+    // Only the organization owner admits users
+    let err = waitlist_action(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::AdmitFromWaitlist {
+            plan_id: 1,
+            count: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    // Nobody is admitted while the plan is full
+    let admit = ExecuteMsg::AdmitFromWaitlist {
+        plan_id: 1,
+        count: 2,
+    };
+    waitlist_action(&mut app, &subscription_hub, ORGANIZATION, admit.clone()).unwrap();
+    assert_eq!(
+        query_waitlist(&app, &subscription_hub),
+        vec![Addr::unchecked(USER2), Addr::unchecked(USER3)]
+    );
+
+    // A freed seat admits only the next user and is reserved for them
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    waitlist_action(&mut app, &subscription_hub, ORGANIZATION, admit).unwrap();
+    assert_eq!(
+        query_waitlist(&app, &subscription_hub),
+        vec![Addr::unchecked(USER3)]
+    );
+    let err = try_subscribe(&mut app, &subscription_hub, USER).unwrap_err();
+    assert_eq!(err, ContractError::PlanFull {}.to_string());
+    try_subscribe(&mut app, &subscription_hub, USER2).unwrap();
+    assert_eq!(active_subscribers(&app, &subscription_hub), 1);
+
+    let err = try_subscribe(&mut app, &subscription_hub, USER3).unwrap_err();
+    assert_eq!(err, ContractError::PlanFull {}.to_string());
+
+    waitlist_action(
+        &mut app,
+        &subscription_hub,
+        USER3,
+        ExecuteMsg::LeaveWaitlist { plan_id: 1 },
+    )
+    .unwrap();
+    assert_eq!(query_waitlist(&app, &subscription_hub), Vec::<Addr>::new());
+
+    let err = waitlist_action(
+        &mut app,
+        &subscription_hub,
+        USER3,
+        ExecuteMsg::LeaveWaitlist { plan_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotWaitlisted {}.to_string());
+}
+
+#[test]
+fn test_waitlist_disabled() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_limited_subscription_plan(&mut app, &subscription_hub, 1, false);
+
+    let err = waitlist_action(
+        &mut app,
+        &subscription_hub,
+        USER,
+        ExecuteMsg::JoinWaitlist { plan_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::WaitlistDisabled {}.to_string());
+}
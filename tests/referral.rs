@@ -0,0 +1,421 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::{Asset, AssetInfo},
+    msg::{ExecuteMsg, QueryMsg, ReferrerStatsResponse, SubscriptionResponse},
+    state::{AffiliateReward, DurationUnit, PlanKind},
+    ContractError,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+fn set_affiliate_reward(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    reward: Option<AffiliateReward>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::SetAffiliateReward { plan_id: 1, reward },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn renew(app: &mut App, subscription_hub: &Addr) {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+}
+
+fn query_referrer_stats(app: &App, subscription_hub: &Addr) -> ReferrerStatsResponse {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::ReferrerStats {
+                referrer: USER3.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+fn native_asset_amount(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::Native {
+            denom: DENOM.to_string(),
+        },
+        amount: Uint128::new(amount),
+    }
+}
+
+#[test]
+fn test_referral_rewards() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    let reward = AffiliateReward {
+        bps: 1_000,
+        recurring: false,
+    };
+    let err =
+        set_affiliate_reward(&mut app, &subscription_hub, USER, Some(reward.clone())).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    let err = set_affiliate_reward(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        Some(AffiliateReward {
+            bps: 0,
+            recurring: false,
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidAffiliateReward {}.to_string());
+    set_affiliate_reward(&mut app, &subscription_hub, ORGANIZATION, Some(reward)).unwrap();
+
+    // Subscribers cannot refer themselves
+    let subscribe = |referrer: &str| ExecuteMsg::SubscribePlan {
+        plan_id: 1,
+        beneficiary: None,
+        coupon: None,
+        max_price: None,
+        proof: None,
+        referrer: Some(referrer.to_string()),
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &subscribe(USER),
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::InvalidReferrer {}.to_string()
+    );
+
+    // The referrer earns the commission from the organization's revenue
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &subscribe(USER3),
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.referrer, Some(Addr::unchecked(USER3)));
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_asset_amount(9_000)]);
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub),
+        ReferrerStatsResponse {
+            referrals: 1,
+            earned: vec![native_asset_amount(1_000)],
+            claimable: vec![native_asset_amount(1_000)],
+        }
+    );
+
+    // Renewals only earn a commission with a recurring reward
+    renew(&mut app, &subscription_hub);
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub).earned,
+        vec![native_asset_amount(1_000)]
+    );
+    set_affiliate_reward(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        Some(AffiliateReward {
+            bps: 1_000,
+            recurring: true,
+        }),
+    )
+    .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH));
+    renew(&mut app, &subscription_hub);
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub).claimable,
+        vec![native_asset_amount(2_000)]
+    );
+
+    // The referrer claims the rewards
+    let balance = query_native_balance(&app, USER3);
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::ClaimReferralRewards {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_native_balance(&app, USER3),
+        balance + Uint128::new(2_000)
+    );
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub),
+        ReferrerStatsResponse {
+            referrals: 1,
+            earned: vec![native_asset_amount(2_000)],
+            claimable: vec![],
+        }
+    );
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub,
+            &ExecuteMsg::ClaimReferralRewards {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::NoReferralRewards {}.to_string()
+    );
+}
+
+#[test]
+fn test_refunded_referral() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Refundable Plan".to_string(),
+            description: "Refundable plan is the best".to_string(),
+            prices: vec![native_asset_amount(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+    set_affiliate_reward(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        Some(AffiliateReward {
+            bps: 1_000,
+            recurring: false,
+        }),
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: Some(USER3.to_string()),
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+
+    // The commission is held with the payment until it is earned
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub),
+        ReferrerStatsResponse {
+            referrals: 1,
+            earned: vec![],
+            claimable: vec![],
+        }
+    );
+
+    // Cancel after half of the period
+    app.update_block(|block| block.time = block.time.plus_seconds(MONTH / 2));
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // The whole unused half is refunded, the referrer only earns on the used half
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_asset_amount(5_000)]
+    );
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub),
+        ReferrerStatsResponse {
+            referrals: 1,
+            earned: vec![native_asset_amount(500)],
+            claimable: vec![native_asset_amount(500)],
+        }
+    );
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_asset_amount(4_500)]);
+}
+
+#[test]
+fn test_metered_referral() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Metered Plan".to_string(),
+            description: "Metered plan is the best".to_string(),
+            prices: vec![native_asset_amount(10)],
+            reference_price: None,
+            kind: Some(PlanKind::Metered { usage_cap: None }),
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+    set_affiliate_reward(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        Some(AffiliateReward {
+            bps: 1_000,
+            recurring: true,
+        }),
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: Some(USER3.to_string()),
+        },
+        &coins(5_000, DENOM),
+    )
+    .unwrap();
+
+    // The referrer earns its commission on the charged usage
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::ReportUsage {
+            subscription_id: 1,
+            units: 100,
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub).earned,
+        vec![native_asset_amount(100)]
+    );
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_asset_amount(900)]);
+}
+
+#[test]
+fn test_referral_without_reward() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    // Referrals of plans without an affiliate reward are not counted
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: Some(USER3.to_string()),
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+    assert_eq!(
+        query_referrer_stats(&app, &subscription_hub),
+        ReferrerStatsResponse {
+            referrals: 0,
+            earned: vec![],
+            claimable: vec![],
+        }
+    );
+}
@@ -0,0 +1,187 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::Executor;
+use subscription_hub::{
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
+    ContractError,
+};
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    let expiration = res.data.expiration;
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::RenewSubscription {
+                subscription_id: 1,
+                max_price: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap();
+    let event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+    assert!(event
+        .attributes
+        .iter()
+        .any(|a| a.key == "action" && a.value == "renew_subscription"));
+
+    // The subscription is extended by one period from its expiration
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(
+        res.data.expiration.seconds() - expiration.seconds(),
+        30 * 24 * 60 * 60
+    );
+    assert_eq!(query_native_balance(&app, USER), Uint128::new(980_000));
+}
+
+#[test]
+fn test_renew_expired_subscription() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(60 * 24 * 60 * 60));
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+
+    // The new period starts when the subscription is renewed
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(
+        res.data.expiration,
+        app.block_info().time.plus_seconds(30 * 24 * 60 * 60)
+    );
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(res);
+}
+
+#[test]
+fn test_renew_too_early() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+
+    // Only one period can be paid in advance
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::RenewSubscription {
+                subscription_id: 1,
+                max_price: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::NotRenewable {}.to_string()
+    );
+}
+
+#[test]
+fn test_renew_canceled_subscription() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::RenewSubscription {
+                subscription_id: 1,
+                max_price: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::AlreadyCanceled {}.to_string()
+    );
+}
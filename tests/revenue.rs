@@ -0,0 +1,463 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, to_binary, Addr, Uint128};
+use cw_multi_test::Executor;
+use subscription_hub::{
+    asset::{Asset, AssetInfo},
+    msg::{ExecuteMsg, QueryMsg, ReceiveMsg, SubscriptionResponse},
+    state::DurationUnit,
+    ContractError,
+};
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let cw20 = instantiate_mock_cw20(&mut app, vec![(USER2, 1_000_000)]);
+    let cw20_price = Asset {
+        info: AssetInfo::Cw20 {
+            address: cw20.clone(),
+        },
+        amount: Uint128::new(50_000),
+    };
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Multi Currency Plan".to_string(),
+            description: "Multi currency plan is the best".to_string(),
+            prices: vec![native_price(10_000), cw20_price.clone()],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Pay with native tokens
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // Pay with CW20 tokens
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        cw20.clone(),
+        &MockCw20ExecuteMsg::Send {
+            contract: subscription_hub.to_string(),
+            amount: Uint128::new(50_000),
+            msg: to_binary(&ReceiveMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 2 },
+        )
+        .unwrap();
+    assert_eq!(res.data.subscriber, USER2);
+    assert_eq!(res.data.price, Some(cw20_price.clone()));
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 2);
+    assert!(res.contains(&native_price(10_000)));
+    assert!(res.contains(&cw20_price));
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::WithdrawRevenue {
+            organization_id: 1,
+            asset: native_price(0).info,
+            amount: None,
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_native_balance(&app, ORGANIZATION),
+        Uint128::new(1_010_000)
+    );
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::WithdrawRevenue {
+            organization_id: 1,
+            asset: cw20_price.info.clone(),
+            amount: Some(Uint128::new(20_000)),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_cw20_balance(&app, &cw20, ORGANIZATION),
+        Uint128::new(20_000)
+    );
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert!(res.contains(&native_price(0)));
+    assert!(res.contains(&Asset {
+        info: cw20_price.info,
+        amount: Uint128::new(30_000),
+    }));
+}
+
+#[test]
+fn test_invalid_payment() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    for funds in [vec![], coins(9_999, DENOM), coins(10_001, DENOM)] {
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                subscription_hub.clone(),
+                &ExecuteMsg::SubscribePlan {
+                    plan_id: 1,
+                    beneficiary: None,
+                    coupon: None,
+                    max_price: None,
+                    proof: None,
+                    referrer: None,
+                },
+                &funds,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            ContractError::InvalidPayment {}.to_string()
+        );
+    }
+}
+
+#[test]
+fn test_refund_to_payer() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Refundable Plan".to_string(),
+            description: "Refundable plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // User pays for the second user's subscription
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: Some(USER2.to_string()),
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+
+    // Move time forward by 15 days
+    app.update_block(|block| block.time = block.time.plus_seconds(1296000));
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // Half of the payment is credited to the payer
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(5_000)]
+    );
+    assert_eq!(query_credits(&app, &subscription_hub, USER2), vec![]);
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(5_000)]);
+}
+
+#[test]
+fn test_invalid_organization_owner() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ORGANIZATION2),
+            subscription_hub,
+            &ExecuteMsg::WithdrawRevenue {
+                organization_id: 1,
+                asset: native_price(0).info,
+                amount: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized {}.to_string()
+    );
+}
+
+#[test]
+fn test_refund_after_withdrawal() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Refundable Plan".to_string(),
+            description: "Refundable plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // The payment is held until it is earned
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Escrow { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(10_000)]);
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![]);
+
+    // Move time forward by 15 days and release the earned half
+    app.update_block(|block| block.time = block.time.plus_seconds(1296000));
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::ProcessSubscription { subscription_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // Withdraw all of the revenue
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::WithdrawRevenue {
+            organization_id: 1,
+            asset: native_price(0).info,
+            amount: None,
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_native_balance(&app, ORGANIZATION),
+        Uint128::new(1_005_000)
+    );
+
+    // The refund is still paid from the escrow
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(5_000)]
+    );
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(subscription_hub, &QueryMsg::Escrow { subscription_id: 1 })
+        .unwrap();
+    assert_eq!(res, vec![]);
+}
+
+#[test]
+fn test_refund_after_early_renewal() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Refundable Plan".to_string(),
+            description: "Refundable plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration: 1,
+            duration_unit: DurationUnit::Month,
+            features: None,
+            metadata: None,
+            cancelable: true,
+            refundable: true,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .unwrap();
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // Renew in advance after 15 days, the unused half of the first period stays in escrow
+    app.update_block(|block| block.time = block.time.plus_seconds(1296000));
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Escrow { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(15_000)]);
+
+    // Canceling refunds the rest of the first period and the whole renewed one
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_credits(&app, &subscription_hub, USER),
+        vec![native_price(15_000)]
+    );
+
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_price(5_000)]);
+}
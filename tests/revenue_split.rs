@@ -0,0 +1,185 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    asset::{Asset, AssetInfo},
+    msg::{ExecuteMsg, QueryMsg, RecipientRevenueResponse},
+    state::RevenueShare,
+    ContractError,
+};
+
+fn set_revenue_split(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    shares: Vec<(&str, u16)>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::SetRevenueSplit {
+            organization_id: 1,
+            shares: shares
+                .into_iter()
+                .map(|(recipient, bps)| RevenueShare {
+                    recipient: Addr::unchecked(recipient),
+                    bps,
+                })
+                .collect(),
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn native_asset() -> AssetInfo {
+    AssetInfo::Native {
+        denom: DENOM.to_string(),
+    }
+}
+
+fn native_asset_amount(amount: u128) -> Asset {
+    Asset {
+        info: native_asset(),
+        amount: Uint128::new(amount),
+    }
+}
+
+#[test]
+fn test_revenue_split() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    let err =
+        set_revenue_split(&mut app, &subscription_hub, USER, vec![(USER2, 3_000)]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    let err = set_revenue_split(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        vec![(USER2, 6_000), (USER3, 6_000)],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidRevenueSplit {}.to_string());
+    let err = set_revenue_split(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        vec![(USER2, 1_000), (USER2, 1_000)],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidRevenueSplit {}.to_string());
+
+    set_revenue_split(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        vec![(USER2, 3_000), (USER3, 2_000)],
+    )
+    .unwrap();
+
+    // The recipients accrue their shares of the incoming revenue
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    let res: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::OrganizationRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![native_asset_amount(5_000)]);
+    let res: Vec<RecipientRevenueResponse> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::RecipientRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            RecipientRevenueResponse {
+                recipient: Addr::unchecked(USER2),
+                claimable: vec![native_asset_amount(3_000)],
+            },
+            RecipientRevenueResponse {
+                recipient: Addr::unchecked(USER3),
+                claimable: vec![native_asset_amount(2_000)],
+            },
+        ]
+    );
+
+    // Recipients can claim their own share
+    let balance = query_native_balance(&app, USER3);
+    let claim = ExecuteMsg::ClaimRevenue {
+        organization_id: 1,
+        asset: native_asset(),
+    };
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &claim,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_native_balance(&app, USER3),
+        balance + Uint128::new(2_000)
+    );
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub.clone(),
+            &claim,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::InsufficientRevenue {}.to_string()
+    );
+
+    // Withdrawing the revenue pays out every recipient
+    subscribe_plan(&mut app, &subscription_hub, ORGANIZATION2, 1);
+    let owner_balance = query_native_balance(&app, ORGANIZATION);
+    let user2_balance = query_native_balance(&app, USER2);
+    let user3_balance = query_native_balance(&app, USER3);
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::WithdrawRevenue {
+            organization_id: 1,
+            asset: native_asset(),
+            amount: None,
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_native_balance(&app, ORGANIZATION),
+        owner_balance + Uint128::new(10_000)
+    );
+    assert_eq!(
+        query_native_balance(&app, USER2),
+        user2_balance + Uint128::new(6_000)
+    );
+    assert_eq!(
+        query_native_balance(&app, USER3),
+        user3_balance + Uint128::new(2_000)
+    );
+
+    let res: Vec<RecipientRevenueResponse> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::RecipientRevenue { organization_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, vec![]);
+}
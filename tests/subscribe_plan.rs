@@ -1,7 +1,10 @@
+// The baseline tests compare booleans with `assert_eq!`
+#![allow(clippy::bool_assert_comparison)]
+
 pub mod helpers;
 use helpers::*;
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{coins, Addr};
 use cw_multi_test::Executor;
 use subscription_hub::{
     msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
@@ -20,8 +23,15 @@ fn test_happy_path() {
     app.execute_contract(
         Addr::unchecked(USER),
         subscription_hub.clone(),
-        &ExecuteMsg::SubscribePlan { plan_id: 1 },
-        &vec![],
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
     )
     .unwrap();
 
@@ -79,8 +89,15 @@ fn test_existing_subscription() {
     app.execute_contract(
         Addr::unchecked(USER),
         subscription_hub.clone(),
-        &ExecuteMsg::SubscribePlan { plan_id: 1 },
-        &vec![],
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
     )
     .unwrap();
 
@@ -88,12 +105,173 @@ fn test_existing_subscription() {
         .execute_contract(
             Addr::unchecked(USER),
             subscription_hub.clone(),
-            &ExecuteMsg::SubscribePlan { plan_id: 1 },
-            &vec![],
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: None,
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::AlreadySubscribed {}.to_string()
+    )
+}
+
+#[test]
+fn test_gift_subscription() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    // User subscribes on behalf of the second user
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: Some(USER2.to_string()),
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.subscriber, USER2);
+    assert_eq!(res.data.payer, USER);
+
+    // The beneficiary is subscribed, the payer is not
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::IsSubscribed {
+                user_address: USER2.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(res);
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(!res);
+
+    // The beneficiary cannot be gifted the same plan twice
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub.clone(),
+            &ExecuteMsg::SubscribePlan {
+                plan_id: 1,
+                beneficiary: Some(USER2.to_string()),
+                coupon: None,
+                max_price: None,
+                proof: None,
+                referrer: None,
+            },
+            &coins(10_000, DENOM),
         )
         .unwrap_err();
     assert_eq!(
         err.source().unwrap().to_string(),
         ContractError::AlreadySubscribed {}.to_string()
+    );
+
+    // Someone else renewing the gift does not take it over from the payer
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub.clone(),
+        &ExecuteMsg::RenewSubscription {
+            subscription_id: 1,
+            max_price: None,
+        },
+        &coins(10_000, DENOM),
     )
+    .unwrap();
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.payer, USER);
+
+    // The payer can cancel the gifted subscription
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::IsSubscribed {
+                user_address: USER2.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(!res);
+}
+
+#[test]
+fn test_resubscribe_after_expiration() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, false);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    // Move time forward by 30 days and 1 second
+    app.update_block(|block| block.time = block.time.plus_seconds(2592001));
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let res: Vec<SubscriptionResponse> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::UserSubscriptions {
+                user_address: USER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].id, 2);
 }
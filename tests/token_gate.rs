@@ -0,0 +1,162 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::{ExecuteMsg, QueryMsg},
+    state::{TokenGate, TokenRequirement},
+    ContractError,
+};
+
+fn set_token_gate(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    gate: Option<TokenGate>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::SetTokenGate { plan_id: 1, gate },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn try_subscribe(
+    app: &mut App,
+    subscription_hub: &Addr,
+    subscriber: &str,
+    amount: u128,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(subscriber),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: None,
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(amount, DENOM),
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+#[test]
+fn test_cw20_gate() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let cw20 = instantiate_mock_cw20(&mut app, vec![(USER, 100), (USER2, 10)]);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    let gate = TokenGate {
+        requirement: TokenRequirement::Cw20 {
+            address: cw20.clone(),
+            min_balance: Uint128::new(50),
+        },
+        holder_discount: Some(20),
+    };
+    let err = set_token_gate(&mut app, &subscription_hub, USER, Some(gate.clone())).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    let err = set_token_gate(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        Some(TokenGate {
+            requirement: TokenRequirement::Cw20 {
+                address: cw20,
+                min_balance: Uint128::zero(),
+            },
+            holder_discount: None,
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTokenGate {}.to_string());
+
+    set_token_gate(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        Some(gate.clone()),
+    )
+    .unwrap();
+    let res: Option<TokenGate> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::TokenGate { plan_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res, Some(gate));
+
+    // Holders subscribe with the holder discount
+    let balance = query_native_balance(&app, USER);
+    try_subscribe(&mut app, &subscription_hub, USER, 8_000).unwrap();
+    assert_eq!(
+        balance - query_native_balance(&app, USER),
+        Uint128::new(8_000)
+    );
+
+    // The balance of USER2 is below the threshold
+    let err = try_subscribe(&mut app, &subscription_hub, USER2, 10_000).unwrap_err();
+    assert_eq!(err, ContractError::TokenGateNotMet {}.to_string());
+
+    // Removing the gate opens the plan to everyone
+    set_token_gate(&mut app, &subscription_hub, ORGANIZATION, None).unwrap();
+    try_subscribe(&mut app, &subscription_hub, USER2, 10_000).unwrap();
+}
+
+#[test]
+fn test_cw721_gate() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+    let cw721 = instantiate_mock_cw721(&mut app, vec![("1", USER)]);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+    set_token_gate(
+        &mut app,
+        &subscription_hub,
+        ORGANIZATION,
+        Some(TokenGate {
+            requirement: TokenRequirement::Cw721 {
+                address: cw721.clone(),
+            },
+            holder_discount: None,
+        }),
+    )
+    .unwrap();
+
+    // Holders pay the full price
+    let balance = query_native_balance(&app, USER);
+    try_subscribe(&mut app, &subscription_hub, USER, 10_000).unwrap();
+    assert_eq!(
+        balance - query_native_balance(&app, USER),
+        Uint128::new(10_000)
+    );
+
+    let err = try_subscribe(&mut app, &subscription_hub, USER2, 10_000).unwrap_err();
+    assert_eq!(err, ContractError::TokenGateNotMet {}.to_string());
+
+    // USER2 can subscribe once they own a token of the collection
+    app.execute_contract(
+        Addr::unchecked(USER),
+        cw721,
+        &MockCw721ExecuteMsg::TransferNft {
+            recipient: USER2.to_string(),
+            token_id: "1".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    try_subscribe(&mut app, &subscription_hub, USER2, 10_000).unwrap();
+}
@@ -0,0 +1,252 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::Executor;
+use subscription_hub::{
+    msg::{ExecuteMsg, QueryMsg, SubscriptionResponse},
+    ContractError,
+};
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &ExecuteMsg::TransferSubscription {
+                subscription_id: 1,
+                recipient: USER2.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    let event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+    assert!(event
+        .attributes
+        .iter()
+        .any(|a| a.key == "action" && a.value == "transfer_subscription"));
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.subscriber, USER2);
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::IsSubscribed {
+                user_address: USER.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(!res);
+
+    let res: bool = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::IsSubscribed {
+                user_address: USER2.to_string(),
+                plan_id: 1,
+            },
+        )
+        .unwrap();
+    assert!(res);
+
+    let res: Vec<SubscriptionResponse> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::UserSubscriptions {
+                user_address: USER.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 0);
+
+    let res: Vec<SubscriptionResponse> = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::SubscriptionPlanSubscriptions {
+                plan_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].data.subscriber, USER2);
+}
+
+#[test]
+fn test_non_transferable_subscription_plan() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1, true);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::TransferSubscription {
+                subscription_id: 1,
+                recipient: USER2.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::NotTransferable {}.to_string()
+    );
+}
+
+#[test]
+fn test_invalid_sender() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER2),
+            subscription_hub,
+            &ExecuteMsg::TransferSubscription {
+                subscription_id: 1,
+                recipient: USER2.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized {}.to_string()
+    );
+}
+
+#[test]
+fn test_recipient_already_subscribed() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+
+    subscribe_plan(&mut app, &subscription_hub, USER, 1);
+    subscribe_plan(&mut app, &subscription_hub, USER2, 1);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub,
+            &ExecuteMsg::TransferSubscription {
+                subscription_id: 1,
+                recipient: USER2.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::AlreadySubscribed {}.to_string()
+    );
+}
+
+#[test]
+fn test_previous_owner_cannot_cancel() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    create_transferable_subscription_plan(&mut app, &subscription_hub, ORGANIZATION, 1);
+
+    // USER pays for a subscription of USER2, who sells it to USER3
+    app.execute_contract(
+        Addr::unchecked(USER),
+        subscription_hub.clone(),
+        &ExecuteMsg::SubscribePlan {
+            plan_id: 1,
+            beneficiary: Some(USER2.to_string()),
+            coupon: None,
+            max_price: None,
+            proof: None,
+            referrer: None,
+        },
+        &coins(10_000, DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        subscription_hub.clone(),
+        &ExecuteMsg::TransferSubscription {
+            subscription_id: 1,
+            recipient: USER3.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: SubscriptionResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Subscription { subscription_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(res.data.payer, USER3);
+
+    // Neither the seller nor the original payer can cancel the subscription anymore
+    for sender in [USER, USER2] {
+        let err = app
+            .execute_contract(
+                Addr::unchecked(sender),
+                subscription_hub.clone(),
+                &ExecuteMsg::CancelPlan { plan_id: 1 },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            ContractError::Unauthorized {}.to_string()
+        );
+    }
+
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        subscription_hub,
+        &ExecuteMsg::CancelPlan { plan_id: 1 },
+        &[],
+    )
+    .unwrap();
+}
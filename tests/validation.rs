@@ -0,0 +1,184 @@
+pub mod helpers;
+use helpers::*;
+
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::ExecuteMsg,
+    state::{DurationUnit, ValidationLimits},
+    ContractError,
+};
+
+fn create_organization_with(
+    app: &mut App,
+    subscription_hub: &Addr,
+    name: &str,
+    website: Option<&str>,
+    metadata: Option<BTreeMap<String, String>>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateOrganization {
+            name: name.to_string(),
+            description: "Test organization is the best".to_string(),
+            handle: None,
+            website: website.map(str::to_string),
+            metadata,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn create_subscription_plan_with(
+    app: &mut App,
+    subscription_hub: &Addr,
+    duration: u8,
+    features: Option<Vec<String>>,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(ORGANIZATION),
+        subscription_hub.clone(),
+        &ExecuteMsg::CreateSubscriptionPlan {
+            organization_id: 1,
+            name: "Test Plan".to_string(),
+            description: "Test plan is the best".to_string(),
+            prices: vec![native_price(10_000)],
+            reference_price: None,
+            kind: None,
+            dunning: None,
+            duration,
+            duration_unit: DurationUnit::Month,
+            features,
+            metadata: None,
+            cancelable: true,
+            refundable: false,
+            transferable: false,
+            pausable: false,
+            max_pause_duration: None,
+            max_subscribers: None,
+            waitlist: false,
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+#[test]
+fn test_invalid_organization() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    let err = create_organization_with(&mut app, &subscription_hub, " ", None, None).unwrap_err();
+    assert_eq!(err, ContractError::EmptyName {}.to_string());
+
+    let err = create_organization_with(&mut app, &subscription_hub, &"a".repeat(65), None, None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::NameTooLong {}.to_string());
+
+    for website in [
+        "example.com",
+        "https://",
+        "https://exa mple.com",
+        "ftp://example.com",
+    ] {
+        let err =
+            create_organization_with(&mut app, &subscription_hub, "Test", Some(website), None)
+                .unwrap_err();
+        assert_eq!(err, ContractError::InvalidUrl {}.to_string());
+    }
+
+    let metadata = (0..17)
+        .map(|index| (index.to_string(), "value".to_string()))
+        .collect::<BTreeMap<String, String>>();
+    let err = create_organization_with(&mut app, &subscription_hub, "Test", None, Some(metadata))
+        .unwrap_err();
+    assert_eq!(err, ContractError::TooManyMetadataEntries {}.to_string());
+
+    let metadata = BTreeMap::from([("key".to_string(), "a".repeat(257))]);
+    let err = create_organization_with(&mut app, &subscription_hub, "Test", None, Some(metadata))
+        .unwrap_err();
+    assert_eq!(err, ContractError::MetadataTooLong {}.to_string());
+
+    create_organization_with(
+        &mut app,
+        &subscription_hub,
+        "Test",
+        Some("https://example.com/about"),
+        Some(BTreeMap::from([("key".to_string(), "value".to_string())])),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_invalid_subscription_plan() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    let err = create_subscription_plan_with(&mut app, &subscription_hub, 0, None).unwrap_err();
+    assert_eq!(err, ContractError::InvalidDuration {}.to_string());
+
+    let features = (0..33).map(|index| index.to_string()).collect();
+    let err =
+        create_subscription_plan_with(&mut app, &subscription_hub, 1, Some(features)).unwrap_err();
+    assert_eq!(err, ContractError::TooManyFeatures {}.to_string());
+
+    let err =
+        create_subscription_plan_with(&mut app, &subscription_hub, 1, Some(vec![String::new()]))
+            .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFeature {}.to_string());
+}
+
+#[test]
+fn test_update_limits() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    let limits = ValidationLimits {
+        max_name_length: 12,
+        max_price: Some(Uint128::new(5_000)),
+        ..ValidationLimits::default()
+    };
+    let update_limits = ExecuteMsg::UpdateConfig {
+        admin: None,
+        oracle: None,
+        oracle_max_staleness: None,
+        limits: Some(limits),
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER),
+            subscription_hub.clone(),
+            &update_limits,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized {}.to_string()
+    );
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        subscription_hub.clone(),
+        &update_limits,
+        &[],
+    )
+    .unwrap();
+
+    let err =
+        create_organization_with(&mut app, &subscription_hub, "Test Organization", None, None)
+            .unwrap_err();
+    assert_eq!(err, ContractError::NameTooLong {}.to_string());
+
+    let err = create_subscription_plan_with(&mut app, &subscription_hub, 1, None).unwrap_err();
+    assert_eq!(err, ContractError::PriceTooLarge {}.to_string());
+}
@@ -0,0 +1,159 @@
+pub mod helpers;
+use helpers::*;
+
+use cosmwasm_std::Addr;
+use cw_multi_test::{App, Executor};
+use subscription_hub::{
+    msg::{ExecuteMsg, OrganizationResponse, QueryMsg},
+    ContractError,
+};
+
+fn verify_organization(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    organization_id: u32,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::VerifyOrganization {
+            organization_id,
+            proof_url: Some("https://example.com/proof".to_string()),
+        },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn revoke_verification(
+    app: &mut App,
+    subscription_hub: &Addr,
+    sender: &str,
+    organization_id: u32,
+) -> Result<(), String> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        subscription_hub.clone(),
+        &ExecuteMsg::RevokeVerification { organization_id },
+        &[],
+    )
+    .map(|_| ())
+    .map_err(|err| err.source().unwrap().to_string())
+}
+
+fn query_organizations(
+    app: &App,
+    subscription_hub: &Addr,
+    verified: Option<bool>,
+) -> Vec<OrganizationResponse> {
+    app.wrap()
+        .query_wasm_smart(
+            subscription_hub,
+            &QueryMsg::Organizations {
+                verified,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_happy_path() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+    create_organization(&mut app, &subscription_hub, USER);
+    verify_organization(&mut app, &subscription_hub, ADMIN, 1).unwrap();
+
+    let res: OrganizationResponse = app
+        .wrap()
+        .query_wasm_smart(
+            subscription_hub.clone(),
+            &QueryMsg::Organization { organization_id: 1 },
+        )
+        .unwrap();
+    assert!(res.verified);
+    let verification = res.verification.unwrap();
+    assert_eq!(verification.verifier, Addr::unchecked(ADMIN));
+    assert_eq!(verification.verified_at, app.block_info().time);
+    assert_eq!(
+        verification.proof_url,
+        Some("https://example.com/proof".to_string())
+    );
+
+    let err = verify_organization(&mut app, &subscription_hub, ADMIN, 1).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyVerified {}.to_string());
+
+    // The list can be filtered by verification
+    let ids = |res: Vec<OrganizationResponse>| res.iter().map(|res| res.id).collect::<Vec<u32>>();
+    assert_eq!(
+        ids(query_organizations(&app, &subscription_hub, None)),
+        vec![1, 2]
+    );
+    assert_eq!(
+        ids(query_organizations(&app, &subscription_hub, Some(true))),
+        vec![1]
+    );
+    assert_eq!(
+        ids(query_organizations(&app, &subscription_hub, Some(false))),
+        vec![2]
+    );
+
+    revoke_verification(&mut app, &subscription_hub, ADMIN, 1).unwrap();
+    assert_eq!(
+        ids(query_organizations(&app, &subscription_hub, Some(true))),
+        Vec::<u32>::new()
+    );
+
+    let err = revoke_verification(&mut app, &subscription_hub, ADMIN, 1).unwrap_err();
+    assert_eq!(err, ContractError::NotVerified {}.to_string());
+}
+
+#[test]
+fn test_delegated_verifiers() {
+    let mut app = mock_app();
+    let subscription_hub = proper_instantiate(&mut app, ADMIN);
+
+    create_organization(&mut app, &subscription_hub, ORGANIZATION);
+
+    // Organizations cannot verify themselves
+    let err = verify_organization(&mut app, &subscription_hub, ORGANIZATION, 1).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER3),
+            subscription_hub.clone(),
+            &ExecuteMsg::SetVerifiers {
+                verifiers: vec![USER3.to_string()],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized {}.to_string()
+    );
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        subscription_hub.clone(),
+        &ExecuteMsg::SetVerifiers {
+            verifiers: vec![USER3.to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+    let res: Vec<Addr> = app
+        .wrap()
+        .query_wasm_smart(subscription_hub.clone(), &QueryMsg::Verifiers {})
+        .unwrap();
+    assert_eq!(res, vec![Addr::unchecked(USER3)]);
+
+    verify_organization(&mut app, &subscription_hub, USER3, 1).unwrap();
+    revoke_verification(&mut app, &subscription_hub, USER3, 1).unwrap();
+}